@@ -25,6 +25,15 @@ where
     parse_buf_line(input.lines(), tfn)
 }
 
+/// Pass the raw bytes of an input file to a given transform
+pub fn parse_input_bytes<T, F>(day: usize, tfn: F) -> Result<T, Box<dyn Error>>
+where
+    F: FnOnce(&[u8]) -> T,
+{
+    let input = Input::new(day)?;
+    Ok(tfn(input.bytes()))
+}
+
 /// Parse an input string to a vector with a given transform
 pub fn parse_test_vec<T, F>(test: &str, tfn: F) -> Result<Vec<T>, Box<dyn Error>>
 where
@@ -94,6 +103,10 @@ impl Input {
         Ok(Self { mmap })
     }
 
+    fn bytes(&self) -> &[u8] {
+        self.mmap.as_ref()
+    }
+
     fn lines(&self) -> Lines<BufReader<&[u8]>> {
         let buf_reader = BufReader::new(self.mmap.as_ref());
 
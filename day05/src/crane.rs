@@ -0,0 +1,38 @@
+/// A crane model which decides how a lifted group of crates lands on the destination stack
+pub trait Crane {
+    /// Returns the name of the crane model
+    fn name(&self) -> &str;
+
+    /// Removes `count` crates from the top of a stack, returning them in the order
+    /// they are to be pushed on to the destination stack.
+    /// The caller guarantees that the stack holds at least `count` crates
+    fn lift(&self, stack: &mut Vec<char>, count: usize) -> Vec<char>;
+}
+
+/// CrateMover 9000 - moves crates one at a time
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn name(&self) -> &str {
+        "CrateMover 9000"
+    }
+
+    fn lift(&self, stack: &mut Vec<char>, count: usize) -> Vec<char> {
+        let mut items = stack.split_off(stack.len() - count);
+        items.reverse();
+        items
+    }
+}
+
+/// CrateMover 9001 - moves multiple crates at once
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn name(&self) -> &str {
+        "CrateMover 9001"
+    }
+
+    fn lift(&self, stack: &mut Vec<char>, count: usize) -> Vec<char> {
+        stack.split_off(stack.len() - count)
+    }
+}
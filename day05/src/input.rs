@@ -0,0 +1,68 @@
+use std::{collections::VecDeque, error::Error};
+
+use crate::stacks::{Move, Stacks};
+
+pub fn get_input(lines: Vec<InputEnt>) -> Result<(Stacks, Vec<Move>), Box<dyn Error>> {
+    let mut stacks = Vec::new();
+    let mut moves = Vec::new();
+
+    for (line_no, line) in lines.into_iter().enumerate() {
+        match line {
+            InputEnt::StackLine(s) => {
+                for (i, c) in s.iter().enumerate() {
+                    if !c.is_ascii_whitespace() {
+                        while stacks.len() < i + 1 {
+                            stacks.push(VecDeque::new())
+                        }
+                        stacks[i].push_front(*c)
+                    }
+                }
+            }
+            InputEnt::MoveLine(m) => {
+                moves.push(parse_move(&m).map_err(|e| format!("Line {}: {e}", line_no + 1))?)
+            }
+            InputEnt::Ignore => (),
+        }
+    }
+
+    Ok((stacks.into(), moves))
+}
+
+/// Parses a move line, converting the one-based stack numbers to indexes
+fn parse_move(line: &str) -> Result<Move, String> {
+    let split: Vec<&str> = line.split_ascii_whitespace().collect();
+
+    let num = |i: usize| {
+        split
+            .get(i)
+            .and_then(|n| n.parse::<usize>().ok())
+            .ok_or_else(|| format!("Invalid move '{line}'"))
+    };
+
+    let stack = |i: usize| match num(i)? {
+        0 => Err(format!("'{line}': stack numbers start at 1")),
+        n => Ok(n - 1),
+    };
+
+    Ok(Move {
+        count: num(1)?,
+        from: stack(3)?,
+        to: stack(5)?,
+    })
+}
+
+pub enum InputEnt {
+    StackLine(Vec<char>),
+    MoveLine(String),
+    Ignore,
+}
+
+pub fn input_transform(line: String) -> InputEnt {
+    if line.trim_start().starts_with('[') {
+        InputEnt::StackLine(line.chars().skip(1).step_by(4).collect())
+    } else if line.starts_with("move") {
+        InputEnt::MoveLine(line)
+    } else {
+        InputEnt::Ignore
+    }
+}
@@ -0,0 +1,3 @@
+pub mod crane;
+pub mod input;
+pub mod stacks;
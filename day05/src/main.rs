@@ -1,121 +1,40 @@
-use std::{collections::VecDeque, error::Error};
+use std::error::Error;
 
 use aoc::input::parse_input_vec;
 
+use day05lib::{
+    crane::{Crane, CrateMover9000, CrateMover9001},
+    input::{get_input, input_transform},
+    stacks::{Move, Stacks},
+};
+
 fn main() -> Result<(), Box<dyn Error>> {
     // Get input
     let (stacks, moves) = get_input(parse_input_vec(5, input_transform)?)?;
 
     // Run parts
-    println!("Part 1: {}", part1(stacks.clone(), &moves));
-    println!("Part 2: {}", part2(stacks, &moves));
+    println!("Part 1: {}", part1(stacks.clone(), &moves)?);
+    println!("Part 2: {}", part2(stacks, &moves)?);
 
     Ok(())
 }
 
-fn part1(mut stacks: Stacks, moves: &[Move]) -> String {
-    for mv in moves {
-        stacks.move_lifo(mv);
-    }
-
-    stacks.top_boxes()
-}
-
-fn part2(mut stacks: Stacks, moves: &[Move]) -> String {
-    for mv in moves {
-        stacks.move_chunk(mv);
-    }
-
-    stacks.top_boxes()
-}
-
-#[derive(Clone)]
-struct Stacks {
-    stacks: Vec<Vec<char>>,
+fn part1(stacks: Stacks, moves: &[Move]) -> Result<String, Box<dyn Error>> {
+    run_crane(stacks, moves, &CrateMover9000)
 }
 
-impl Stacks {
-    fn top_boxes(&self) -> String {
-        self.stacks.iter().map(|s| s.last().unwrap()).collect()
-    }
-
-    fn move_lifo(&mut self, mv: &Move) {
-        for _ in 0..mv.count {
-            let item = self.stacks[mv.from].pop().unwrap();
-            self.stacks[mv.to].push(item);
-        }
-    }
-
-    fn move_chunk(&mut self, mv: &Move) {
-        let pos = self.stacks[mv.from].len() - mv.count;
-        let mut items = self.stacks[mv.from].split_off(pos);
-        self.stacks[mv.to].append(&mut items);
-    }
+fn part2(stacks: Stacks, moves: &[Move]) -> Result<String, Box<dyn Error>> {
+    run_crane(stacks, moves, &CrateMover9001)
 }
 
-impl From<Vec<VecDeque<char>>> for Stacks {
-    fn from(vvdq: Vec<VecDeque<char>>) -> Self {
-        Self {
-            stacks: vvdq.into_iter().map(Vec::from).collect(),
-        }
-    }
-}
-
-#[derive(Debug)]
-struct Move {
-    count: usize,
-    from: usize,
-    to: usize,
-}
+fn run_crane(
+    mut stacks: Stacks,
+    moves: &[Move],
+    crane: &dyn Crane,
+) -> Result<String, Box<dyn Error>> {
+    stacks.simulate(crane, moves, |_, _, _| Ok(()))?;
 
-// Input parsing
-
-fn get_input(lines: Vec<InputEnt>) -> Result<(Stacks, Vec<Move>), Box<dyn Error>> {
-    let (stacks, moves) = lines.into_iter().fold(
-        (Vec::new(), Vec::new()),
-        |(mut stacks, mut moves), line| match line {
-            InputEnt::StackLine(s) => {
-                for (i, c) in s.iter().enumerate() {
-                    if !c.is_ascii_whitespace() {
-                        while stacks.len() < i + 1 {
-                            stacks.push(VecDeque::new())
-                        }
-                        stacks[i].push_front(*c)
-                    }
-                }
-                (stacks, moves)
-            }
-            InputEnt::MoveLine(m) => {
-                moves.push(m);
-                (stacks, moves)
-            }
-            _ => (stacks, moves),
-        },
-    );
-
-    Ok((stacks.into(), moves))
-}
-
-enum InputEnt {
-    StackLine(Vec<char>),
-    MoveLine(Move),
-    Ignore,
-}
-
-fn input_transform(line: String) -> InputEnt {
-    if line.trim_start().starts_with('[') {
-        InputEnt::StackLine(line.chars().skip(1).step_by(4).collect())
-    } else if line.starts_with("move") {
-        let split: Vec<&str> = line.split_ascii_whitespace().collect();
-
-        InputEnt::MoveLine(Move {
-            count: split[1].parse::<usize>().unwrap(),
-            from: split[3].parse::<usize>().unwrap() - 1,
-            to: split[5].parse::<usize>().unwrap() - 1,
-        })
-    } else {
-        InputEnt::Ignore
-    }
+    stacks.top_boxes()
 }
 
 #[cfg(test)]
@@ -139,7 +58,91 @@ move 1 from 1 to 2
     fn test1() {
         let (stacks, moves) =
             get_input(parse_test_vec(EXAMPLE1, input_transform).unwrap()).unwrap();
-        assert_eq!(part1(stacks.clone(), &moves), "CMZ");
-        assert_eq!(part2(stacks, &moves), "MCD");
+        assert_eq!(part1(stacks.clone(), &moves).unwrap(), "CMZ");
+        assert_eq!(part2(stacks, &moves).unwrap(), "MCD");
+    }
+
+    #[test]
+    fn test_render() {
+        let (mut stacks, moves) =
+            get_input(parse_test_vec(EXAMPLE1, input_transform).unwrap()).unwrap();
+
+        // Initial diagram should match the input
+        assert_eq!(
+            format!("{stacks}"),
+            EXAMPLE1.split("\n\n").next().unwrap().to_string() + "\n"
+        );
+
+        // Render after the first step
+        stacks.apply(&CrateMover9000, &moves[0]).unwrap();
+        assert_eq!(
+            format!("{stacks}"),
+            "[D]        \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n"
+        );
+        assert_eq!(format!("{}", moves[0]), "move 1 from 2 to 1");
+    }
+
+    #[test]
+    fn test_invalid_move() {
+        let (mut stacks, _) =
+            get_input(parse_test_vec(EXAMPLE1, input_transform).unwrap()).unwrap();
+
+        let moves = [
+            Move {
+                count: 4,
+                from: 0,
+                to: 1,
+            },
+            Move {
+                count: 1,
+                from: 0,
+                to: 3,
+            },
+        ];
+
+        for mv in &moves {
+            assert!(stacks.apply(&CrateMover9001, mv).is_err());
+        }
+
+        // Stacks should be untouched
+        assert_eq!(stacks.top_boxes().unwrap(), "NDP");
+
+        // Empty stacks have no top crate
+        let mv = Move {
+            count: 1,
+            from: 1,
+            to: 0,
+        };
+        stacks.apply(&CrateMover9000, &mv).unwrap();
+        stacks.apply(&CrateMover9000, &mv).unwrap();
+        stacks.apply(&CrateMover9000, &mv).unwrap();
+        assert_eq!(
+            stacks.top_boxes().unwrap_err().to_string(),
+            "Stack 2 is empty"
+        );
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let input = EXAMPLE1.replace("move 3 from 1 to 3", "move 3 from 0 to 3");
+        assert_eq!(
+            get_input(parse_test_vec(&input, input_transform).unwrap())
+                .err()
+                .unwrap()
+                .to_string(),
+            "Line 7: 'move 3 from 0 to 3': stack numbers start at 1"
+        );
+
+        let input = EXAMPLE1.replace("move 2 from 2 to 1", "move 2 from x to 1");
+        assert_eq!(
+            get_input(parse_test_vec(&input, input_transform).unwrap())
+                .err()
+                .unwrap()
+                .to_string(),
+            "Line 8: Invalid move 'move 2 from x to 1'"
+        );
+
+        let input = EXAMPLE1.replace("move 1 from 1 to 2", "move 1 from 1");
+        assert!(get_input(parse_test_vec(&input, input_transform).unwrap()).is_err());
     }
 }
@@ -0,0 +1,144 @@
+use std::{collections::VecDeque, error::Error, fmt};
+
+use crate::crane::Crane;
+
+#[derive(Clone)]
+pub struct Stacks {
+    stacks: Vec<Vec<char>>,
+}
+
+impl Stacks {
+    /// Returns the number of stacks
+    pub fn len(&self) -> usize {
+        self.stacks.len()
+    }
+
+    /// Returns true if there are no stacks
+    pub fn is_empty(&self) -> bool {
+        self.stacks.is_empty()
+    }
+
+    /// Returns the crates in a stack, bottom first
+    pub fn stack(&self, idx: usize) -> &[char] {
+        &self.stacks[idx]
+    }
+
+    /// Returns the height of the tallest stack
+    pub fn max_height(&self) -> usize {
+        self.stacks.iter().map(|s| s.len()).max().unwrap_or(0)
+    }
+
+    /// Returns the total number of crates in all stacks
+    pub fn crate_count(&self) -> usize {
+        self.stacks.iter().map(|s| s.len()).sum()
+    }
+
+    /// Returns the crate on the top of each stack, failing if any stack is empty
+    pub fn top_boxes(&self) -> Result<String, Box<dyn Error>> {
+        let tops = self
+            .stacks
+            .iter()
+            .enumerate()
+            .map(|(i, s)| s.last().ok_or_else(|| format!("Stack {} is empty", i + 1)))
+            .collect::<Result<String, _>>()?;
+
+        Ok(tops)
+    }
+
+    /// Performs a move with a given crane, checking that the move is possible first
+    pub fn apply(&mut self, crane: &dyn Crane, mv: &Move) -> Result<(), Box<dyn Error>> {
+        for stack in [mv.from, mv.to] {
+            if stack >= self.stacks.len() {
+                Err(format!("'{mv}': stack {} does not exist", stack + 1))?
+            }
+        }
+
+        let available = self.stacks[mv.from].len();
+
+        if mv.count > available {
+            Err(format!(
+                "'{mv}': stack {} only has {available} crates",
+                mv.from + 1
+            ))?
+        }
+
+        let mut items = crane.lift(&mut self.stacks[mv.from], mv.count);
+        self.stacks[mv.to].append(&mut items);
+
+        Ok(())
+    }
+
+    /// Performs a list of moves with a given crane, calling back after each step
+    pub fn simulate<F>(
+        &mut self,
+        crane: &dyn Crane,
+        moves: &[Move],
+        mut step_cb: F,
+    ) -> Result<(), Box<dyn Error>>
+    where
+        F: FnMut(usize, &Move, &Stacks) -> Result<(), Box<dyn Error>>,
+    {
+        for (step, mv) in moves.iter().enumerate() {
+            self.apply(crane, mv)
+                .map_err(|e| format!("Move {}: {e}", step + 1))?;
+
+            step_cb(step, mv, self)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<Vec<VecDeque<char>>> for Stacks {
+    fn from(vvdq: Vec<VecDeque<char>>) -> Self {
+        Self {
+            stacks: vvdq.into_iter().map(Vec::from).collect(),
+        }
+    }
+}
+
+/// Renders the stacks in the puzzle input's diagram format
+impl fmt::Display for Stacks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in (0..self.max_height()).rev() {
+            let line = self
+                .stacks
+                .iter()
+                .map(|s| match s.get(row) {
+                    Some(c) => format!("[{c}]"),
+                    None => "   ".to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            writeln!(f, "{line}")?;
+        }
+
+        let numbers = (1..=self.stacks.len())
+            .map(|n| format!("{n:^3}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        writeln!(f, "{numbers}")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Move {
+    pub count: usize,
+    pub from: usize,
+    pub to: usize,
+}
+
+/// Renders the move in the puzzle input's format
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "move {} from {} to {}",
+            self.count,
+            self.from + 1,
+            self.to + 1
+        )
+    }
+}
@@ -0,0 +1,144 @@
+use std::error::Error;
+
+use hsl::HSL;
+use lazy_static::lazy_static;
+
+use aoc::{gif::Gif, input::parse_input_vec};
+
+use day05lib::{
+    crane::{Crane, CrateMover9000, CrateMover9001},
+    input::{get_input, input_transform},
+    stacks::{Move, Stacks},
+};
+
+fn main() -> Result<(), Box<dyn Error>> {
+    // Get input
+    let (stacks, moves) = get_input(parse_input_vec(5, input_transform)?)?;
+
+    // Create visualisations
+    println!("Generating visualisations...");
+    vis(
+        stacks.clone(),
+        &moves,
+        &CrateMover9000,
+        "vis/day05-1-anim.gif",
+    )?;
+    vis(stacks, &moves, &CrateMover9001, "vis/day05-2-anim.gif")?;
+
+    Ok(())
+}
+
+const CRATE_WIDTH: usize = 3;
+const GAP: usize = 1;
+const X_SCALE: u16 = 4;
+const Y_SCALE: u16 = 8;
+const DELAY: u16 = 5;
+const FINAL_DELAY: u16 = 1000;
+
+const BG_COLOUR: u8 = 0;
+const FLOOR_COLOUR: u8 = 1;
+const CRATE_COLOUR_BASE: u8 = 2; // Crate colours for A-Z
+const MOVED_COLOUR_BASE: u8 = CRATE_COLOUR_BASE + 26; // Highlighted crate colours for A-Z
+
+lazy_static! {
+    /// GIF colour palette
+    pub static ref COLOUR_PALETTE: Vec<[u8; 3]> = {
+        let letter_colours = |l: f64| {
+            (0..26).map(move |i| {
+                let hsl = HSL {
+                    h: (i as f64 * 360_f64) / 26_f64,
+                    s: 0.6_f64,
+                    l,
+                };
+
+                let (r, g, b) = hsl.to_rgb();
+
+                [r, g, b]
+            })
+        };
+
+        [[0x00, 0x00, 0x00], [0x80, 0x80, 0x80]] // Black background, grey floor
+            .into_iter()
+            .chain(letter_colours(0.35_f64)) // Crates
+            .chain(letter_colours(0.7_f64)) // Moved crates
+            .collect::<Vec<_>>()
+    };
+}
+
+fn vis(
+    mut stacks: Stacks,
+    moves: &[Move],
+    crane: &dyn Crane,
+    file: &str,
+) -> Result<(), Box<dyn Error>> {
+    println!("{}...", crane.name());
+
+    // Every crate could end up on a single stack
+    let width = (stacks.len() * (CRATE_WIDTH + GAP)) + GAP;
+    let height = stacks.crate_count() + 1;
+
+    let mut gif = Gif::new(
+        file,
+        &COLOUR_PALETTE,
+        width as u16,
+        height as u16,
+        X_SCALE,
+        Y_SCALE,
+    )?;
+
+    // Draw the starting position
+    gif.draw_frame(draw_stacks(&stacks, None, width, height), DELAY)?;
+
+    // Draw each move
+    stacks.simulate(crane, moves, |_, mv, stacks| {
+        gif.draw_frame(draw_stacks(stacks, Some(mv), width, height), DELAY)
+    })?;
+
+    // Draw the final position
+    gif.draw_frame(draw_stacks(&stacks, None, width, height), DELAY)?;
+    gif.delay(FINAL_DELAY)?;
+
+    Ok(())
+}
+
+fn draw_stacks(
+    stacks: &Stacks,
+    last_move: Option<&Move>,
+    width: usize,
+    height: usize,
+) -> Vec<Vec<u8>> {
+    let mut frame = vec![vec![BG_COLOUR; width]; height];
+
+    // Draw the floor
+    frame[height - 1] = vec![FLOOR_COLOUR; width];
+
+    for s in 0..stacks.len() {
+        let stack = stacks.stack(s);
+
+        // Work out which crates have just been moved on to this stack
+        let moved_from = match last_move {
+            Some(mv) if mv.to == s => stack.len() - mv.count,
+            _ => stack.len(),
+        };
+
+        let x = GAP + (s * (CRATE_WIDTH + GAP));
+
+        for (i, c) in stack.iter().enumerate() {
+            let letter = (*c as u8).wrapping_sub(b'A') % 26;
+
+            let colour = if i >= moved_from {
+                MOVED_COLOUR_BASE + letter
+            } else {
+                CRATE_COLOUR_BASE + letter
+            };
+
+            let y = height - 2 - i;
+
+            for pixel in &mut frame[y][x..x + CRATE_WIDTH] {
+                *pixel = colour;
+            }
+        }
+    }
+
+    frame
+}
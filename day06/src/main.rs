@@ -1,44 +1,91 @@
-use std::{collections::HashSet, error::Error};
+use std::error::Error;
 
-use aoc::input::parse_input_line;
+use aoc::input::parse_input_bytes;
 
 fn main() -> Result<(), Box<dyn Error>> {
-    // Get input
-    let input = parse_input_line(6, input_transform)?;
+    // Get input and run parts
+    let (part1, part2) = parse_input_bytes(6, |input| {
+        let input = input.trim_ascii_end();
+        (part1(input), part2(input))
+    })?;
 
-    // Run parts
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
+    println!("Part 1: {}", part1);
+    println!("Part 2: {}", part2);
 
     Ok(())
 }
 
-fn part1(input: &[char]) -> usize {
-    find_unique(input, 4)
+fn part1(input: &[u8]) -> usize {
+    Markers::new(input, 4).next().expect("No marker found")
 }
 
-fn part2(input: &[char]) -> usize {
-    find_unique(input, 14)
+fn part2(input: &[u8]) -> usize {
+    Markers::new(input, 14).next().expect("No marker found")
 }
 
-fn find_unique(input: &[char], window: usize) -> usize {
-    input
-        .windows(window)
-        .enumerate()
-        .find(|(_, elems)| HashSet::<char>::from_iter(elems.iter().copied()).len() == window)
-        .unwrap()
-        .0
-        + window
+/// Iterator over the positions after each window of unique bytes in the input.
+/// Keeps a rolling count of each byte in the window and the number of bytes
+/// which appear more than once, so each step is constant time
+struct Markers<'a> {
+    input: &'a [u8],
+    window: usize,
+    pos: usize,
+    counts: [usize; 256],
+    dups: usize,
 }
 
-// Input parsing
+impl<'a> Markers<'a> {
+    fn new(input: &'a [u8], window: usize) -> Self {
+        assert!(window > 0, "Window size must be greater than zero");
+
+        Self {
+            input,
+            window,
+            pos: 0,
+            counts: [0; 256],
+            dups: 0,
+        }
+    }
+}
+
+impl Iterator for Markers<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pos < self.input.len() {
+            // Add the incoming byte
+            let count = &mut self.counts[self.input[self.pos] as usize];
+            *count += 1;
+
+            if *count == 2 {
+                self.dups += 1;
+            }
+
+            // Remove the outgoing byte
+            if self.pos >= self.window {
+                let count = &mut self.counts[self.input[self.pos - self.window] as usize];
+                *count -= 1;
+
+                if *count == 1 {
+                    self.dups -= 1;
+                }
+            }
 
-fn input_transform(line: String) -> Vec<char> {
-    line.chars().collect()
+            self.pos += 1;
+
+            if self.pos >= self.window && self.dups == 0 {
+                return Some(self.pos);
+            }
+        }
+
+        None
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
+
     use super::*;
 
     const EXAMPLE1: &str = "mjqjpqmgbljsphdztnvjfqwrcgsmlb";
@@ -49,36 +96,55 @@ mod tests {
 
     #[test]
     fn test1() {
-        let input: Vec<char> = EXAMPLE1.chars().collect();
-        assert_eq!(part1(&input), 7);
-        assert_eq!(part2(&input), 19);
+        let input = EXAMPLE1.as_bytes();
+        assert_eq!(part1(input), 7);
+        assert_eq!(part2(input), 19);
     }
 
     #[test]
     fn test2() {
-        let input: Vec<char> = EXAMPLE2.chars().collect();
-        assert_eq!(part1(&input), 5);
-        assert_eq!(part2(&input), 23);
+        let input = EXAMPLE2.as_bytes();
+        assert_eq!(part1(input), 5);
+        assert_eq!(part2(input), 23);
     }
 
     #[test]
     fn test3() {
-        let input: Vec<char> = EXAMPLE3.chars().collect();
-        assert_eq!(part1(&input), 6);
-        assert_eq!(part2(&input), 23);
+        let input = EXAMPLE3.as_bytes();
+        assert_eq!(part1(input), 6);
+        assert_eq!(part2(input), 23);
     }
 
     #[test]
     fn test4() {
-        let input: Vec<char> = EXAMPLE4.chars().collect();
-        assert_eq!(part1(&input), 10);
-        assert_eq!(part2(&input), 29);
+        let input = EXAMPLE4.as_bytes();
+        assert_eq!(part1(input), 10);
+        assert_eq!(part2(input), 29);
     }
 
     #[test]
     fn test5() {
-        let input: Vec<char> = EXAMPLE5.chars().collect();
-        assert_eq!(part1(&input), 11);
-        assert_eq!(part2(&input), 26);
+        let input = EXAMPLE5.as_bytes();
+        assert_eq!(part1(input), 11);
+        assert_eq!(part2(input), 26);
+    }
+
+    #[test]
+    fn test_all_markers() {
+        for example in [EXAMPLE1, EXAMPLE2, EXAMPLE3, EXAMPLE4, EXAMPLE5] {
+            let input = example.as_bytes();
+
+            for window in 1..=input.len() + 1 {
+                // Brute force each window with a hash set
+                let expected = input
+                    .windows(window)
+                    .enumerate()
+                    .filter(|(_, elems)| HashSet::<&u8>::from_iter(elems.iter()).len() == window)
+                    .map(|(i, _)| i + window)
+                    .collect::<Vec<_>>();
+
+                assert_eq!(Markers::new(input, window).collect::<Vec<_>>(), expected);
+            }
+        }
     }
 }
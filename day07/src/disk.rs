@@ -0,0 +1,106 @@
+use std::{
+    error::Error,
+    fs::{self, File},
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::fs::{DirId, FileSystem};
+
+/// Creates the file system under an existing directory on disk.
+/// Files are created as sparse files of the recorded size
+pub fn export(fs: &FileSystem, dest: &Path) -> Result<(), Box<dyn Error>> {
+    export_dir(fs, FileSystem::ROOT, dest)
+}
+
+/// Creates the file system under a new uniquely named directory in the system
+/// temporary directory, returning its path
+pub fn export_temp(fs: &FileSystem) -> Result<PathBuf, Box<dyn Error>> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let dest = loop {
+        let path = std::env::temp_dir().join(format!(
+            "day07-{}-{}",
+            process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        if !path.exists() {
+            break path;
+        }
+    };
+
+    fs::create_dir(&dest)?;
+    export(fs, &dest)?;
+
+    Ok(dest)
+}
+
+/// Generates a terminal transcript in the puzzle input format for a directory on disk.
+/// Entries are listed in name order and symbolic links are skipped
+pub fn transcript(root: &Path) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut lines = vec!["$ cd /".to_string()];
+
+    transcript_dir(root, &mut lines)?;
+
+    Ok(lines)
+}
+
+fn export_dir(fs: &FileSystem, id: DirId, path: &Path) -> Result<(), Box<dyn Error>> {
+    let dir = fs.dir(id);
+
+    for (name, size) in dir.files() {
+        File::create(path.join(name))?.set_len(size as u64)?;
+    }
+
+    for (name, child) in dir.dirs() {
+        let child_path = path.join(name);
+
+        fs::create_dir(&child_path)?;
+        export_dir(fs, child, &child_path)?;
+    }
+
+    Ok(())
+}
+
+fn transcript_dir(path: &Path, lines: &mut Vec<String>) -> Result<(), Box<dyn Error>> {
+    let mut entries = fs::read_dir(path)?.collect::<Result<Vec<_>, _>>()?;
+
+    entries.sort_by_key(|e| e.file_name());
+
+    let mut subdirs = Vec::new();
+
+    lines.push("$ ls".to_string());
+
+    for entry in entries {
+        let name = entry
+            .file_name()
+            .into_string()
+            .map_err(|name| format!("{}: name is not valid UTF-8", Path::new(&name).display()))?;
+
+        if name.chars().any(char::is_whitespace) {
+            Err(format!(
+                "{}: name contains whitespace",
+                entry.path().display()
+            ))?
+        }
+
+        let metadata = entry.metadata()?;
+
+        if metadata.is_dir() {
+            lines.push(format!("dir {name}"));
+            subdirs.push((name, entry.path()));
+        } else if metadata.is_file() {
+            lines.push(format!("{} {name}", metadata.len()));
+        }
+    }
+
+    for (name, path) in subdirs {
+        lines.push(format!("$ cd {name}"));
+        transcript_dir(&path, lines)?;
+        lines.push("$ cd ..".to_string());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,314 @@
+use std::{collections::BTreeMap, error::Error, fmt};
+
+use crate::input::InputEnt;
+
+/// Index of a directory in the file system
+pub type DirId = usize;
+
+/// Directory in the file system tree
+#[derive(Debug, Default)]
+pub struct Dir {
+    name: String,
+    parent: Option<DirId>,
+    dirs: BTreeMap<String, DirId>,
+    files: BTreeMap<String, usize>,
+    listed: bool,
+}
+
+impl Dir {
+    /// Returns the name of the directory
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the parent directory, or None for the root
+    pub fn parent(&self) -> Option<DirId> {
+        self.parent
+    }
+
+    /// Returns an iterator over the subdirectory names and ids
+    pub fn dirs(&self) -> impl Iterator<Item = (&str, DirId)> {
+        self.dirs.iter().map(|(name, id)| (name.as_str(), *id))
+    }
+
+    /// Returns an iterator over the file names and sizes
+    pub fn files(&self) -> impl Iterator<Item = (&str, usize)> {
+        self.files.iter().map(|(name, size)| (name.as_str(), *size))
+    }
+}
+
+/// File system tree reconstructed from a terminal transcript.
+/// Directories are stored in creation order so a parent always precedes its children
+#[derive(Debug)]
+pub struct FileSystem {
+    dirs: Vec<Dir>,
+}
+
+impl FileSystem {
+    /// The root directory
+    pub const ROOT: DirId = 0;
+
+    /// Builds the file system tree from terminal transcript lines
+    pub fn build(input: &[InputEnt]) -> Result<Self, Box<dyn Error>> {
+        let mut fs = Self {
+            dirs: vec![Dir::default()],
+        };
+
+        let mut cwd = Self::ROOT;
+        let mut listing: Option<Listing> = None;
+
+        for (line, item) in input.iter().enumerate() {
+            let line = line + 1;
+
+            // Finish any listing when the next command is seen
+            if !matches!(item, InputEnt::OutputDir(_) | InputEnt::OutputFile(_, _)) {
+                if let Some(listing) = listing.take() {
+                    fs.add_listing(cwd, listing)?;
+                }
+            }
+
+            match item {
+                InputEnt::CommandCdRoot => cwd = Self::ROOT,
+                InputEnt::CommandCdUp => {
+                    cwd = fs.dirs[cwd]
+                        .parent
+                        .ok_or(format!("Line {line}: cd .. from the root directory"))?
+                }
+                InputEnt::CommandCd(to) => {
+                    cwd = *fs.dirs[cwd].dirs.get(to).ok_or(format!(
+                        "Line {line}: directory {} not found",
+                        fs.child_path(cwd, to)
+                    ))?
+                }
+                InputEnt::CommandLs => listing = Some(Listing::default()),
+                InputEnt::OutputDir(name) => listing
+                    .as_mut()
+                    .ok_or(format!("Line {line}: output outside of ls"))?
+                    .add(name, None)
+                    .map_err(|e| format!("Line {line}: {}: {e}", fs.child_path(cwd, name)))?,
+                InputEnt::OutputFile(size, name) => listing
+                    .as_mut()
+                    .ok_or(format!("Line {line}: output outside of ls"))?
+                    .add(name, Some(*size))
+                    .map_err(|e| format!("Line {line}: {}: {e}", fs.child_path(cwd, name)))?,
+            }
+        }
+
+        if let Some(listing) = listing.take() {
+            fs.add_listing(cwd, listing)?;
+        }
+
+        Ok(fs)
+    }
+
+    /// Returns a directory by id
+    pub fn dir(&self, id: DirId) -> &Dir {
+        &self.dirs[id]
+    }
+
+    /// Returns the number of directories
+    pub fn dir_count(&self) -> usize {
+        self.dirs.len()
+    }
+
+    /// Returns the total size of each directory, indexed by directory id
+    pub fn dir_sizes(&self) -> Vec<usize> {
+        let mut sizes = self
+            .dirs
+            .iter()
+            .map(|d| d.files.values().sum())
+            .collect::<Vec<usize>>();
+
+        // Children always come after their parent so work backwards
+        for id in (1..self.dirs.len()).rev() {
+            if let Some(parent) = self.dirs[id].parent {
+                sizes[parent] += sizes[id];
+            }
+        }
+
+        sizes
+    }
+
+    /// Returns the full path of a directory
+    pub fn path(&self, id: DirId) -> String {
+        match self.dirs[id].parent {
+            None => "/".to_string(),
+            Some(parent) => self.child_path(parent, &self.dirs[id].name),
+        }
+    }
+
+    /// Returns a `du` style report of the total size of each directory, deepest first.
+    /// Sizes are displayed in K, M, G etc. if `human` is true
+    pub fn du(&self, human: bool) -> String {
+        let sizes = self.dir_sizes();
+        let mut report = String::new();
+
+        self.walk_post_order(Self::ROOT, &mut |id| {
+            let size = if human {
+                human_size(sizes[id])
+            } else {
+                sizes[id].to_string()
+            };
+
+            report += &format!("{size}\t{}\n", self.path(id));
+        });
+
+        report
+    }
+
+    /// Calls back for each directory in a subtree, children first
+    fn walk_post_order<F>(&self, id: DirId, cb: &mut F)
+    where
+        F: FnMut(DirId),
+    {
+        for child in self.dirs[id].dirs.values() {
+            self.walk_post_order(*child, cb);
+        }
+
+        cb(id)
+    }
+
+    /// Returns the path of a named entry in a directory
+    fn child_path(&self, id: DirId, name: &str) -> String {
+        match self.dirs[id].parent {
+            None => format!("/{name}"),
+            Some(_) => format!("{}/{name}", self.path(id)),
+        }
+    }
+
+    /// Adds the output of an ls command to a directory, checking it against any previous listing
+    fn add_listing(&mut self, id: DirId, listing: Listing) -> Result<(), Box<dyn Error>> {
+        let dir = &self.dirs[id];
+
+        if dir.listed {
+            // Check the listing matches the previous one
+            let same = dir.files == listing.files
+                && dir.dirs.len() == listing.dirs.len()
+                && listing.dirs.iter().all(|name| dir.dirs.contains_key(name));
+
+            if !same {
+                Err(format!(
+                    "Listing of {} differs from a previous listing",
+                    self.path(id)
+                ))?
+            }
+        } else {
+            for name in listing.dirs {
+                let new_id = self.dirs.len();
+
+                self.dirs.push(Dir {
+                    name: name.clone(),
+                    parent: Some(id),
+                    ..Default::default()
+                });
+
+                self.dirs[id].dirs.insert(name, new_id);
+            }
+
+            let dir = &mut self.dirs[id];
+            dir.files = listing.files;
+            dir.listed = true;
+        }
+
+        Ok(())
+    }
+}
+
+/// Renders the tree in the puzzle description's format
+impl fmt::Display for FileSystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sizes = self.dir_sizes();
+
+        fn write_dir(
+            fs: &FileSystem,
+            sizes: &[usize],
+            f: &mut fmt::Formatter<'_>,
+            id: DirId,
+            name: &str,
+            depth: usize,
+        ) -> fmt::Result {
+            let indent = "  ".repeat(depth);
+            let dir = &fs.dirs[id];
+
+            writeln!(f, "{indent}- {name} (dir, size={})", sizes[id])?;
+
+            // Merge directories and files in name order
+            let mut entries = dir
+                .dirs()
+                .map(|(name, id)| (name, Some(id), 0))
+                .chain(dir.files().map(|(name, size)| (name, None, size)))
+                .collect::<Vec<_>>();
+
+            entries.sort();
+
+            for (name, id, size) in entries {
+                match id {
+                    Some(id) => write_dir(fs, sizes, f, id, name, depth + 1)?,
+                    None => writeln!(f, "{indent}  - {name} (file, size={size})")?,
+                }
+            }
+
+            Ok(())
+        }
+
+        write_dir(self, &sizes, f, Self::ROOT, "/", 0)
+    }
+}
+
+/// Output of a single ls command
+#[derive(Default)]
+struct Listing {
+    dirs: Vec<String>,
+    files: BTreeMap<String, usize>,
+}
+
+impl Listing {
+    /// Adds a directory (no size) or file to the listing. Names must be a single path
+    /// component so the tree can't refer outside itself when exported to disk
+    fn add(&mut self, name: &str, size: Option<usize>) -> Result<(), Box<dyn Error>> {
+        if name.is_empty() || name == "." || name == ".." || name.contains(['/', '\\']) {
+            Err("invalid name")?
+        }
+
+        if self.dirs.iter().any(|d| d == name) || self.files.contains_key(name) {
+            Err("listed more than once")?
+        }
+
+        match size {
+            None => self.dirs.push(name.to_string()),
+            Some(size) => {
+                self.files.insert(name.to_string(), size);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Formats a size with a 1024 based unit suffix
+fn human_size(size: usize) -> String {
+    const UNITS: [&str; 6] = ["K", "M", "G", "T", "P", "E"];
+
+    if size < 1024 {
+        return size.to_string();
+    }
+
+    let mut value = size as f64;
+    let mut unit = 0;
+
+    loop {
+        value /= 1024_f64;
+
+        if value < 1024_f64 || unit == UNITS.len() - 1 {
+            break;
+        }
+
+        unit += 1;
+    }
+
+    if value < 10_f64 {
+        format!("{:.1}{}", value, UNITS[unit])
+    } else {
+        format!("{:.0}{}", value, UNITS[unit])
+    }
+}
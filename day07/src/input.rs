@@ -0,0 +1,29 @@
+pub enum InputEnt {
+    CommandCdRoot,
+    CommandCdUp,
+    CommandCd(String),
+    CommandLs,
+    OutputDir(String),
+    OutputFile(usize, String),
+}
+
+pub fn input_transform(line: String) -> InputEnt {
+    let mut split = line.split_whitespace();
+
+    match split.next().unwrap() {
+        "$" => match split.next().unwrap() {
+            "cd" => match split.next().unwrap() {
+                ".." => InputEnt::CommandCdUp,
+                "/" => InputEnt::CommandCdRoot,
+                x => InputEnt::CommandCd(x.to_string()),
+            },
+            "ls" => InputEnt::CommandLs,
+            x => panic!("Unknown command {x}"),
+        },
+        "dir" => InputEnt::OutputDir(split.next().unwrap().to_string()),
+        x => InputEnt::OutputFile(
+            x.parse::<usize>().unwrap(),
+            split.next().unwrap().to_string(),
+        ),
+    }
+}
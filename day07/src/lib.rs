@@ -0,0 +1,4 @@
+pub mod disk;
+pub mod fs;
+pub mod input;
+pub mod planner;
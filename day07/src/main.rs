@@ -1,126 +1,54 @@
-use std::{collections::HashMap, error::Error};
+use std::env;
+use std::error::Error;
 
 use aoc::input::parse_input_vec;
 
+use day07lib::{fs::FileSystem, input::input_transform, planner::Planner};
+
 const TOTAL_SPACE: usize = 70_000_000;
 const NEEDED_SPACE: usize = 30_000_000;
+const SMALL_DIR_SIZE: usize = 100_000;
 
 fn main() -> Result<(), Box<dyn Error>> {
     // Get input
     let input = parse_input_vec(7, input_transform)?;
 
     // Build file system tree
-    let tree = build_tree(&input);
-
-    // Run parts
-    println!("Part 1: {}", part1(&tree));
-    println!("Part 2: {}", part2(&tree));
-
-    Ok(())
-}
-
-#[derive(Debug, Default)]
-struct Dir {
-    size: usize,
-}
-
-fn part1(tree: &HashMap<String, Dir>) -> usize {
-    tree.values()
-        .filter_map(|d| if d.size < 100_000 { Some(d.size) } else { None })
-        .sum()
-}
+    let fs = FileSystem::build(&input)?;
 
-fn part2(tree: &HashMap<String, Dir>) -> usize {
-    let total = tree.get("").unwrap().size;
-    let cur_free = TOTAL_SPACE - total;
-    let to_free = NEEDED_SPACE - cur_free;
+    // Create the disk cleanup planner
+    let planner = Planner::new(TOTAL_SPACE, NEEDED_SPACE);
 
-    tree.values()
-        .filter_map(|d| if d.size > to_free { Some(d.size) } else { None })
-        .min()
-        .unwrap()
-}
-
-fn build_tree(input: &[InputEnt]) -> HashMap<String, Dir> {
-    let mut cwd = "".to_string();
-
-    let mut tree: HashMap<String, Dir> = HashMap::new();
-    tree.insert(cwd.clone(), Default::default());
-
-    for item in input {
-        match item {
-            InputEnt::CommandCdRoot => cwd = "".to_string(),
-            InputEnt::CommandCdUp => cwd.truncate(cwd.rfind('/').unwrap_or(0)),
-            InputEnt::CommandCd(to) => {
-                if cwd.is_empty() {
-                    cwd = to.to_string();
-                } else {
-                    cwd = format!("{cwd}/{to}");
-                }
-            }
-            InputEnt::CommandLs => {}
-            InputEnt::OutputDir(dir) => {
-                let path = if cwd.is_empty() {
-                    dir.to_string()
-                } else {
-                    format!("{cwd}/{dir}")
-                };
-
-                tree.insert(path, Default::default());
-            }
-            InputEnt::OutputFile(size) => {
-                let mut dir = cwd.clone();
-
-                loop {
-                    let d = tree.get_mut(&dir).unwrap();
-                    d.size += size;
-
-                    if dir.is_empty() {
-                        break;
-                    }
+    // Run parts
+    println!("Part 1: {}", part1(&fs, SMALL_DIR_SIZE));
+    println!("Part 2: {}", part2(&fs, &planner)?);
 
-                    dir.truncate(dir.rfind('/').unwrap_or(0));
-                }
-            }
-        }
+    // Output the best multi-directory plan if asked for
+    if env::args().any(|arg| arg == "--plan") {
+        println!("Minimal deletion plan:");
+        print!("{}", planner.minimal(&fs)?.report(&fs));
     }
 
-    tree
+    Ok(())
 }
 
-// Input parsing
-
-enum InputEnt {
-    CommandCdRoot,
-    CommandCdUp,
-    CommandCd(String),
-    CommandLs,
-    OutputDir(String),
-    OutputFile(usize),
+fn part1(fs: &FileSystem, max_size: usize) -> usize {
+    fs.dir_sizes()
+        .into_iter()
+        .filter(|size| *size < max_size)
+        .sum()
 }
 
-fn input_transform(line: String) -> InputEnt {
-    let mut split = line.split_whitespace();
-
-    match split.next().unwrap() {
-        "$" => match split.next().unwrap() {
-            "cd" => match split.next().unwrap() {
-                ".." => InputEnt::CommandCdUp,
-                "/" => InputEnt::CommandCdRoot,
-                x => InputEnt::CommandCd(x.to_string()),
-            },
-            "ls" => InputEnt::CommandLs,
-            x => panic!("Unknown command {x}"),
-        },
-        "dir" => InputEnt::OutputDir(split.next().unwrap().to_string()),
-        x => InputEnt::OutputFile(x.parse::<usize>().unwrap()),
-    }
+fn part2(fs: &FileSystem, planner: &Planner) -> Result<usize, Box<dyn Error>> {
+    Ok(planner.single(fs)?.freed)
 }
 
 #[cfg(test)]
 mod tests {
     use aoc::input::parse_test_vec;
 
+    use day07lib::disk::{export_temp, transcript};
+
     use super::*;
 
     const EXAMPLE1: &str = r"$ cd /
@@ -151,8 +79,173 @@ $ ls
     #[test]
     fn test1() {
         let input = parse_test_vec(EXAMPLE1, input_transform).unwrap();
-        let tree = build_tree(&input);
-        assert_eq!(part1(&tree), 95437);
-        assert_eq!(part2(&tree), 24933642);
+        let fs = FileSystem::build(&input).unwrap();
+        let planner = Planner::new(TOTAL_SPACE, NEEDED_SPACE);
+        assert_eq!(part1(&fs, SMALL_DIR_SIZE), 95437);
+        assert_eq!(part2(&fs, &planner).unwrap(), 24933642);
+    }
+
+    #[test]
+    fn test_planner() {
+        let input = parse_test_vec(EXAMPLE1, input_transform).unwrap();
+        let fs = FileSystem::build(&input).unwrap();
+
+        // Need 8381165 - /d (24933642) is the best single directory but
+        // b.txt, c.dat and the files in /d are not deletable on their own
+        let plan = Planner::new(TOTAL_SPACE, NEEDED_SPACE)
+            .minimal(&fs)
+            .unwrap();
+        assert_eq!(plan.freed, 24933642);
+
+        // Need 94000 - /a (94853) is better than anything else
+        let plan = Planner::new(48381165 + 1000, 95000).minimal(&fs).unwrap();
+        assert_eq!(plan.to_free, 94000);
+        assert_eq!(
+            plan.report(&fs),
+            "# Need to free 94000, freeing 94853 in 1 directories\nrm -rf /a # 94853\n"
+        );
+
+        // Need 500 - /a/e (584) rather than anything bigger
+        let plan = Planner::new(48381165 + 1000, 1500).minimal(&fs).unwrap();
+        assert_eq!(plan.freed, 584);
+
+        // Already enough space
+        let plan = Planner::new(48381165 + 1000, 1000).minimal(&fs).unwrap();
+        assert!(plan.dirs.is_empty());
+
+        // Disk too small
+        assert!(Planner::new(1000, 1000).minimal(&fs).is_err());
+
+        // Not enough deletable space
+        assert!(Planner::new(48381165, 48381166).minimal(&fs).is_err());
+
+        // Only deleting the root frees enough
+        let input = parse_test_vec(
+            "$ cd /\n$ ls\n10 a\ndir b\n$ cd b\n$ ls\n5 x\n",
+            input_transform,
+        )
+        .unwrap();
+        let fs = FileSystem::build(&input).unwrap();
+        let planner = Planner::new(20, 17);
+
+        assert_eq!(planner.single(&fs).unwrap().dirs, [FileSystem::ROOT]);
+        assert!(planner.minimal(&fs).is_err());
+    }
+
+    #[test]
+    fn test_planner_multiple() {
+        // Two sibling directories free less than the parent
+        let input = parse_test_vec(
+            "$ cd /\n$ ls\ndir a\n$ cd a\n$ ls\ndir b\ndir c\ndir d\n$ cd b\n$ ls\n60 x\n$ cd ..\n$ cd c\n$ ls\n50 x\n$ cd ..\n$ cd d\n$ ls\n45 x\n",
+            input_transform,
+        )
+        .unwrap();
+        let fs = FileSystem::build(&input).unwrap();
+
+        // Need 100 - /a (155) is the only single directory, /a/b and /a/d free 105
+        let planner = Planner::new(155, 100);
+        assert_eq!(planner.single(&fs).unwrap().freed, 155);
+
+        let plan = planner.minimal(&fs).unwrap();
+        assert_eq!(plan.freed, 105);
+        assert_eq!(
+            plan.report(&fs),
+            "# Need to free 100, freeing 105 in 2 directories\nrm -rf /a/b # 60\nrm -rf /a/d # 45\n"
+        );
+    }
+
+    #[test]
+    fn test_reports() {
+        let input = parse_test_vec(EXAMPLE1, input_transform).unwrap();
+        let fs = FileSystem::build(&input).unwrap();
+
+        assert_eq!(
+            fs.to_string(),
+            "- / (dir, size=48381165)
+  - a (dir, size=94853)
+    - e (dir, size=584)
+      - i (file, size=584)
+    - f (file, size=29116)
+    - g (file, size=2557)
+    - h.lst (file, size=62596)
+  - b.txt (file, size=14848514)
+  - c.dat (file, size=8504156)
+  - d (dir, size=24933642)
+    - d.ext (file, size=5626152)
+    - d.log (file, size=8033020)
+    - j (file, size=4060174)
+    - k (file, size=7214296)
+"
+        );
+
+        assert_eq!(
+            fs.du(false),
+            "584\t/a/e\n94853\t/a\n24933642\t/d\n48381165\t/\n"
+        );
+        assert_eq!(fs.du(true), "584\t/a/e\n93K\t/a\n24M\t/d\n46M\t/\n");
+    }
+
+    #[test]
+    fn test_disk_round_trip() {
+        let input = parse_test_vec(EXAMPLE1, input_transform).unwrap();
+        let fs = FileSystem::build(&input).unwrap();
+
+        // Create on disk, removing it again when done
+        struct TempDir(std::path::PathBuf);
+
+        impl Drop for TempDir {
+            fn drop(&mut self) {
+                let _ = std::fs::remove_dir_all(&self.0);
+            }
+        }
+
+        let dir = TempDir(export_temp(&fs).unwrap());
+        assert_eq!(
+            std::fs::metadata(dir.0.join("d").join("k")).unwrap().len(),
+            7214296
+        );
+
+        // Generate transcript from the disk and parse it
+        let lines = transcript(&dir.0).unwrap();
+
+        let input = parse_test_vec(&(lines.join("\n") + "\n"), input_transform).unwrap();
+        let fs2 = FileSystem::build(&input).unwrap();
+
+        assert_eq!(fs2.to_string(), fs.to_string());
+    }
+
+    #[test]
+    fn test_errors() {
+        // cd in to a directory which hasn't been listed
+        let input = parse_test_vec("$ cd /\n$ ls\ndir a\n$ cd b\n", input_transform).unwrap();
+        assert!(FileSystem::build(&input).is_err());
+
+        // cd .. from the root
+        let input = parse_test_vec("$ cd /\n$ cd ..\n", input_transform).unwrap();
+        assert!(FileSystem::build(&input).is_err());
+
+        // Names which would refer outside the tree
+        for name in ["..", ".", "/etc/x", "a/b", "a\\b"] {
+            let input =
+                parse_test_vec(&format!("$ cd /\n$ ls\n10 {name}\n"), input_transform).unwrap();
+            assert!(FileSystem::build(&input).is_err());
+
+            let input =
+                parse_test_vec(&format!("$ cd /\n$ ls\ndir {name}\n"), input_transform).unwrap();
+            assert!(FileSystem::build(&input).is_err());
+        }
+
+        // Directory listed twice with different contents
+        let input =
+            parse_test_vec("$ cd /\n$ ls\n10 a\n$ cd /\n$ ls\n20 a\n", input_transform).unwrap();
+        assert!(FileSystem::build(&input).is_err());
+
+        // Directory listed twice with the same contents
+        let input = parse_test_vec(
+            "$ cd /\n$ ls\n10 a\ndir b\n$ cd /\n$ ls\ndir b\n10 a\n",
+            input_transform,
+        )
+        .unwrap();
+        assert!(FileSystem::build(&input).is_ok());
     }
 }
@@ -0,0 +1,271 @@
+use std::error::Error;
+
+use crate::fs::{DirId, FileSystem};
+
+/// Plans which directories to delete to free up space on a disk
+pub struct Planner {
+    disk_size: usize,
+    required_free: usize,
+}
+
+/// Directories to delete and the space that will be freed
+pub struct Plan {
+    pub to_free: usize,
+    pub freed: usize,
+    pub dirs: Vec<DirId>,
+}
+
+impl Planner {
+    /// Creates a new planner for a given disk size and amount of free space required
+    pub fn new(disk_size: usize, required_free: usize) -> Self {
+        Self {
+            disk_size,
+            required_free,
+        }
+    }
+
+    /// Finds the smallest single directory which frees enough space
+    pub fn single(&self, fs: &FileSystem) -> Result<Plan, Box<dyn Error>> {
+        let sizes = fs.dir_sizes();
+        let to_free = self.to_free(sizes[FileSystem::ROOT])?;
+
+        if to_free == 0 {
+            return Ok(Plan::empty());
+        }
+
+        let (freed, id) = sizes
+            .iter()
+            .enumerate()
+            .filter(|(_, size)| **size >= to_free)
+            .map(|(id, size)| (*size, id))
+            .min()
+            .ok_or("Not enough space can be freed")?;
+
+        Ok(Plan {
+            to_free,
+            freed,
+            dirs: vec![id],
+        })
+    }
+
+    /// Finds the set of directories which frees enough space while deleting the least.
+    /// Directories in the set never contain each other so nothing is counted twice, and
+    /// the root is never included
+    pub fn minimal(&self, fs: &FileSystem) -> Result<Plan, Box<dyn Error>> {
+        // The best single directory is an upper bound on the space to free
+        let single = self.single(fs)?;
+
+        if single.dirs.is_empty() {
+            return Ok(single);
+        }
+
+        let sizes = fs.dir_sizes();
+        let to_free = single.to_free;
+        let bound = single.freed;
+
+        // Number the directories in pre-order and work out where each subtree ends
+        let (order, ends) = pre_order(fs);
+
+        // Most which can be freed from each position on, by deleting whole subtrees
+        let mut rest = vec![0; order.len() + 1];
+
+        for pos in (0..order.len()).rev() {
+            rest[pos] = sizes[order[pos]] + rest[ends[pos]];
+        }
+
+        // Totals reachable at the current pre-order position which don't yet free enough,
+        // in order. Totals reached by deleting a directory are held until the end of its
+        // subtree. Each total is recorded with the directory deleted to reach it at the
+        // position it becomes reachable, and the smallest total freeing enough is kept
+        let mut active = vec![0];
+        let mut pending: Vec<Vec<(Vec<usize>, usize)>> = vec![Vec::new(); order.len() + 1];
+        let mut created = vec![Vec::new(); order.len()];
+        let mut best: Option<(usize, usize)> = None;
+
+        // Start after the root so it's never deleted
+        for pos in 1..order.len() {
+            for (totals, deleted) in std::mem::take(&mut pending[pos]) {
+                active = merge(&active, &totals, deleted, &mut created[pos]);
+            }
+
+            created[pos].sort();
+
+            // Drop totals which can no longer free enough
+            active.retain(|total| total + rest[pos] >= to_free);
+
+            // Delete the directory
+            let size = sizes[order[pos]];
+
+            let mut deleted = active
+                .iter()
+                .map(|total| total + size)
+                .take_while(|total| *total <= bound)
+                .collect::<Vec<_>>();
+
+            let enough = deleted.partition_point(|total| *total < to_free);
+
+            if let Some(total) = deleted.get(enough) {
+                if best.is_none_or(|(freed, _)| *total < freed) {
+                    best = Some((*total, pos));
+                }
+            }
+
+            deleted.truncate(enough);
+
+            // Deleting the directory skips the rest of its subtree
+            let end = ends[pos];
+
+            deleted.retain(|total| total + rest[end] >= to_free);
+
+            if !deleted.is_empty() {
+                pending[end].push((deleted, pos));
+            }
+        }
+
+        let (freed, mut pos) =
+            best.ok_or("Not enough space can be freed without deleting the root")?;
+
+        // Work back through the deleted directories. The total before each was reachable
+        // at its position, so was last created before then
+        let mut dirs = Vec::new();
+        let mut total = freed;
+
+        loop {
+            dirs.push(order[pos]);
+            total -= sizes[order[pos]];
+
+            if total == 0 {
+                break;
+            }
+
+            pos = (1..=pos)
+                .rev()
+                .find_map(|p| {
+                    created[p]
+                        .binary_search_by_key(&total, |(t, _)| *t)
+                        .ok()
+                        .map(|i| created[p][i].1)
+                })
+                .expect("Unable to find deleted directory");
+        }
+
+        Ok(Plan {
+            to_free,
+            freed,
+            dirs,
+        })
+    }
+
+    /// Calculates how much space needs to be freed given the space used
+    fn to_free(&self, used: usize) -> Result<usize, Box<dyn Error>> {
+        if used > self.disk_size {
+            Err(format!(
+                "Used space {used} exceeds the disk size {}",
+                self.disk_size
+            ))?
+        }
+
+        Ok(self.required_free.saturating_sub(self.disk_size - used))
+    }
+}
+
+impl Plan {
+    /// Plan with nothing to delete
+    fn empty() -> Self {
+        Self {
+            to_free: 0,
+            freed: 0,
+            dirs: Vec::new(),
+        }
+    }
+
+    /// Returns the deletion plan as shell commands
+    pub fn report(&self, fs: &FileSystem) -> String {
+        let sizes = fs.dir_sizes();
+
+        let mut paths = self
+            .dirs
+            .iter()
+            .map(|id| (fs.path(*id), sizes[*id]))
+            .collect::<Vec<_>>();
+
+        paths.sort();
+
+        let mut report = format!(
+            "# Need to free {}, freeing {} in {} directories\n",
+            self.to_free,
+            self.freed,
+            paths.len()
+        );
+
+        for (path, size) in paths {
+            report += &format!("rm -rf {path} # {size}\n");
+        }
+
+        report
+    }
+}
+
+/// Merges two ordered sets of totals, recording those which are new along with the
+/// directory deleted to reach them
+fn merge(
+    active: &[usize],
+    totals: &[usize],
+    deleted: usize,
+    created: &mut Vec<(usize, usize)>,
+) -> Vec<usize> {
+    let mut merged = Vec::with_capacity(active.len() + totals.len());
+    let (mut i, mut j) = (0, 0);
+
+    while i < active.len() || j < totals.len() {
+        match (active.get(i), totals.get(j)) {
+            (Some(a), Some(t)) if a == t => {
+                merged.push(*a);
+                i += 1;
+                j += 1;
+            }
+            (Some(a), Some(t)) if a < t => {
+                merged.push(*a);
+                i += 1;
+            }
+            (Some(a), None) => {
+                merged.push(*a);
+                i += 1;
+            }
+            (_, Some(t)) => {
+                merged.push(*t);
+                created.push((*t, deleted));
+                j += 1;
+            }
+            (None, None) => unreachable!(),
+        }
+    }
+
+    merged
+}
+
+/// Returns directory ids in pre-order along with the position after the end of each subtree
+fn pre_order(fs: &FileSystem) -> (Vec<DirId>, Vec<usize>) {
+    let mut order = Vec::with_capacity(fs.dir_count());
+    let mut ends = vec![0; fs.dir_count()];
+    let mut stack = vec![(FileSystem::ROOT, None)];
+
+    while let Some((id, pos)) = stack.pop() {
+        match pos {
+            Some(pos) => {
+                // Subtree finished - record where it ends
+                ends[pos] = order.len();
+            }
+            None => {
+                stack.push((id, Some(order.len())));
+                order.push(id);
+
+                for (_, child) in fs.dir(id).dirs() {
+                    stack.push((child, None));
+                }
+            }
+        }
+    }
+
+    (order, ends)
+}
@@ -0,0 +1,106 @@
+/// Edge of the forest
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edge {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+impl Edge {
+    /// All edges
+    pub const ALL: [Edge; 4] = [Edge::Left, Edge::Right, Edge::Top, Edge::Bottom];
+
+    /// Returns the bit representing this edge in a visibility mask
+    pub fn mask(&self) -> u8 {
+        1 << (*self as u8)
+    }
+}
+
+/// Per-tree visibility and scenic scores for a forest
+pub struct Forest {
+    /// Bit mask of edges each tree is visible from, indexed by [y][x]
+    pub visible: Vec<Vec<u8>>,
+    /// Scenic score of each tree, indexed by [y][x]
+    pub scenic: Vec<Vec<usize>>,
+}
+
+impl Forest {
+    /// Calculates the visibility and scenic score of every tree in linear time
+    pub fn new(heights: &[Vec<u8>]) -> Self {
+        let height = heights.len();
+        let width = heights.first().map_or(0, |row| row.len());
+
+        let mut visible = vec![vec![0; width]; height];
+        let mut scenic = vec![vec![1; width]; height];
+
+        for edge in Edge::ALL {
+            sweep(heights, edge, |x, y, dist, vis| {
+                scenic[y][x] *= dist;
+
+                if vis {
+                    visible[y][x] |= edge.mask();
+                }
+            });
+        }
+
+        Self { visible, scenic }
+    }
+
+    /// Returns the number of trees visible from any edge
+    pub fn visible_count(&self) -> usize {
+        self.visible.iter().flatten().filter(|v| **v != 0).count()
+    }
+
+    /// Returns the highest scenic score
+    pub fn max_scenic(&self) -> usize {
+        self.scenic.iter().flatten().copied().max().unwrap_or(0)
+    }
+}
+
+/// Sweeps every row or column of the forest moving away from an edge, calling back with
+/// the position of each tree, its viewing distance toward the edge and whether it is visible
+/// from the edge. A stack of trees in descending height order which are not yet blocked
+/// from view gives the first tree at least as tall as the current one
+pub fn sweep<F>(heights: &[Vec<u8>], edge: Edge, mut cb: F)
+where
+    F: FnMut(usize, usize, usize, bool),
+{
+    let height = heights.len();
+    let width = heights.first().map_or(0, |row| row.len());
+
+    let (lines, line_len) = match edge {
+        Edge::Left | Edge::Right => (height, width),
+        Edge::Top | Edge::Bottom => (width, height),
+    };
+
+    let mut stack: Vec<(usize, u8)> = Vec::with_capacity(line_len);
+
+    for line in 0..lines {
+        stack.clear();
+
+        for pos in 0..line_len {
+            // Convert line and position from the edge to coordinates
+            let (x, y) = match edge {
+                Edge::Left => (pos, line),
+                Edge::Right => (width - pos - 1, line),
+                Edge::Top => (line, pos),
+                Edge::Bottom => (line, height - pos - 1),
+            };
+
+            let tree = heights[y][x];
+
+            // Remove shorter trees
+            while matches!(stack.last(), Some((_, h)) if *h < tree) {
+                stack.pop();
+            }
+
+            match stack.last() {
+                None => cb(x, y, pos, true),
+                Some((blocker, _)) => cb(x, y, pos - blocker, false),
+            }
+
+            stack.push((pos, tree));
+        }
+    }
+}
@@ -0,0 +1,5 @@
+pub type InputEnt = Vec<u8>;
+
+pub fn input_transform(line: String) -> InputEnt {
+    line.chars().map(|c| c as u8 - b'0').collect()
+}
@@ -0,0 +1,221 @@
+use std::error::Error;
+
+use hsl::HSL;
+use lazy_static::lazy_static;
+
+use aoc::gif::{Gif, IdenticalAction};
+use aoc::input::parse_input_vec;
+
+use day08lib::forest::{sweep, Edge, Forest};
+use day08lib::input::{input_transform, InputEnt};
+
+fn main() -> Result<(), Box<dyn Error>> {
+    // Get input
+    let input = parse_input_vec(8, input_transform)?;
+
+    // Create visualisations
+    println!("Generating visualisations...");
+    vis(&input, "vis/day08-anim.gif", "vis/day08-final.gif")?;
+
+    Ok(())
+}
+
+const SCALE: u16 = 6;
+const SWEEP_DELAY: u16 = 3;
+const PAUSE_DELAY: u16 = 200;
+const FINAL_DELAY: u16 = 1000;
+
+const HEIGHTS: u8 = 10;
+const HEAT_COLOURS: u8 = 64;
+
+const TERRAIN_BASE: u8 = 0; // Green by height
+const EDGE_BASE: u8 = TERRAIN_BASE + HEIGHTS; // Edge colour by height for each edge
+const VISIBLE_BASE: u8 = EDGE_BASE + (HEIGHTS * 4); // Grey by height
+const HEAT_BASE: u8 = VISIBLE_BASE + HEIGHTS; // Blue to red
+const FRONT_COLOUR: u8 = HEAT_BASE + HEAT_COLOURS; // White
+const SIGHTLINE_COLOUR: u8 = FRONT_COLOUR + 1; // Pale yellow
+const BEST_COLOUR: u8 = SIGHTLINE_COLOUR + 1; // Magenta
+
+/// Hue of the overlay for trees visible from each edge
+const EDGE_HUES: [f64; 4] = [0_f64, 60_f64, 190_f64, 280_f64];
+
+lazy_static! {
+    /// GIF colour palette
+    pub static ref COLOUR_PALETTE: Vec<[u8; 3]> = {
+        let hsl_rgb = |h: f64, s: f64, l: f64| {
+            let (r, g, b) = HSL { h, s, l }.to_rgb();
+            [r, g, b]
+        };
+
+        let by_height = move |h: f64, s: f64| {
+            (0..HEIGHTS).map(move |height| hsl_rgb(h, s, 0.15_f64 + (height as f64 * 0.05_f64)))
+        };
+
+        by_height(120_f64, 0.6_f64) // Terrain
+            .chain(EDGE_HUES.iter().flat_map(|h| by_height(*h, 0.8_f64))) // Edge overlays
+            .chain(by_height(0_f64, 0_f64)) // Visible from any edge
+            .chain((0..HEAT_COLOURS).map(|i| {
+                hsl_rgb(240_f64 - ((i as f64 * 240_f64) / (HEAT_COLOURS - 1) as f64), 1_f64, 0.5_f64)
+            })) // Heat map
+            .chain([[0xff, 0xff, 0xff], [0xff, 0xff, 0x80], [0xff, 0x00, 0xff]])
+            .collect::<Vec<_>>()
+    };
+}
+
+fn vis(input: &[InputEnt], anim_file: &str, final_file: &str) -> Result<(), Box<dyn Error>> {
+    let forest = Forest::new(input);
+
+    let height = input.len();
+    let width = input[0].len();
+
+    let mut gif = Gif::new(
+        anim_file,
+        &COLOUR_PALETTE,
+        width as u16,
+        height as u16,
+        SCALE,
+        SCALE,
+    )?;
+
+    // Draw the terrain
+    let terrain = input
+        .iter()
+        .map(|row| row.iter().map(|h| TERRAIN_BASE + h).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    gif.draw_frame(terrain.clone(), PAUSE_DELAY)?;
+
+    // Animate the sweep from each edge
+    let mut overlay = terrain.clone();
+
+    for (e, edge) in Edge::ALL.iter().enumerate() {
+        // Collect the distance from the edge of each tree visible from it
+        let mut visible = Vec::new();
+
+        sweep(input, *edge, |x, y, dist, vis| {
+            if vis {
+                visible.push((dist, x, y));
+            }
+        });
+
+        visible.sort();
+
+        // Move the front away from the edge
+        let line_len = match edge {
+            Edge::Left | Edge::Right => width,
+            Edge::Top | Edge::Bottom => height,
+        };
+
+        let mut next = visible.iter().peekable();
+
+        for pos in 0..line_len {
+            while let Some((_, x, y)) = next.next_if(|(dist, _, _)| *dist == pos) {
+                overlay[*y][*x] = EDGE_BASE + (e as u8 * HEIGHTS) + input[*y][*x];
+            }
+
+            let mut frame = overlay.clone();
+
+            match edge {
+                Edge::Left => (0..height).for_each(|y| frame[y][pos] = FRONT_COLOUR),
+                Edge::Right => (0..height).for_each(|y| frame[y][width - pos - 1] = FRONT_COLOUR),
+                Edge::Top => frame[pos].fill(FRONT_COLOUR),
+                Edge::Bottom => frame[height - pos - 1].fill(FRONT_COLOUR),
+            }
+
+            gif.draw_frame(frame, SWEEP_DELAY)?;
+        }
+
+        gif.draw_frame(overlay.clone(), PAUSE_DELAY)?;
+    }
+
+    // Draw all visible trees
+    let mut frame = terrain;
+
+    for (y, row) in forest.visible.iter().enumerate() {
+        for (x, vis) in row.iter().enumerate() {
+            if *vis != 0 {
+                frame[y][x] = VISIBLE_BASE + input[y][x];
+            }
+        }
+    }
+
+    gif.draw_frame(frame, PAUSE_DELAY)?;
+
+    // Draw the scenic score heat map with the best tree's sightlines
+    let heat_map = heat_map(&forest);
+
+    gif.draw_frame(heat_map.clone(), PAUSE_DELAY)?;
+
+    let sightlines = sightlines(input, &forest, heat_map);
+
+    gif.draw_frame_identical_check(sightlines.clone(), FINAL_DELAY, IdenticalAction::Delay)?;
+
+    // Draw the final frame on its own
+    let mut final_gif = Gif::new(
+        final_file,
+        &COLOUR_PALETTE,
+        width as u16,
+        height as u16,
+        SCALE,
+        SCALE,
+    )?;
+
+    final_gif.draw_frame(sightlines, 0)?;
+
+    Ok(())
+}
+
+/// Draws the scenic scores on a logarithmic scale
+fn heat_map(forest: &Forest) -> Vec<Vec<u8>> {
+    let max = ((forest.max_scenic() + 1) as f64).ln();
+
+    forest
+        .scenic
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|score| {
+                    HEAT_BASE
+                        + ((((*score + 1) as f64).ln() / max) * (HEAT_COLOURS - 1) as f64) as u8
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Draws the sightlines from the tree with the best scenic score
+fn sightlines(input: &[InputEnt], forest: &Forest, mut frame: Vec<Vec<u8>>) -> Vec<Vec<u8>> {
+    let (bx, by) = forest
+        .scenic
+        .iter()
+        .enumerate()
+        .flat_map(|(y, row)| row.iter().enumerate().map(move |(x, s)| (s, x, y)))
+        .max()
+        .map(|(_, x, y)| (x, y))
+        .unwrap();
+
+    let tree = input[by][bx];
+
+    for (dx, dy) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+        let (mut x, mut y) = (bx as isize, by as isize);
+
+        loop {
+            x += dx;
+            y += dy;
+
+            if x < 0 || y < 0 || x >= input[0].len() as isize || y >= input.len() as isize {
+                break;
+            }
+
+            frame[y as usize][x as usize] = SIGHTLINE_COLOUR;
+
+            if input[y as usize][x as usize] >= tree {
+                break;
+            }
+        }
+    }
+
+    frame[by][bx] = BEST_COLOUR;
+
+    frame
+}
@@ -0,0 +1,21 @@
+use crate::rope::Delta;
+
+pub type Instruction = (u32, Delta);
+
+pub fn input_transform(line: String) -> Instruction {
+    let split: Vec<&str> = line.split_whitespace().collect();
+
+    let amt = split[1].parse::<u32>().unwrap();
+
+    match split[0] {
+        "U" => (amt, (0, -1)),
+        "D" => (amt, (0, 1)),
+        "L" => (amt, (-1, 0)),
+        "R" => (amt, (1, 0)),
+        "UL" => (amt, (-1, -1)),
+        "UR" => (amt, (1, -1)),
+        "DL" => (amt, (-1, 1)),
+        "DR" => (amt, (1, 1)),
+        _ => panic!("Unknown instruction {line}"),
+    }
+}
@@ -1,7 +1,12 @@
-use std::{collections::HashSet, error::Error};
+use std::error::Error;
 
 use aoc::input::parse_input_vec;
 
+use day09lib::{
+    input::{input_transform, Instruction},
+    rope::Rope,
+};
+
 fn main() -> Result<(), Box<dyn Error>> {
     // Get input
     let input = parse_input_vec(9, input_transform)?;
@@ -14,97 +19,19 @@ fn main() -> Result<(), Box<dyn Error>> {
 }
 
 fn part1(input: &[Instruction]) -> usize {
-    move_rope(input, 1)
+    Rope::simulate(input, 1).tail_visited()
 }
 
 fn part2(input: &[Instruction]) -> usize {
-    move_rope(input, 9)
-}
-
-fn move_rope(input: &[Instruction], tail_cnt: usize) -> usize {
-    let mut pos = vec![Pos::default(); tail_cnt + 1];
-    let mut tailpositions = HashSet::new();
-
-    tailpositions.insert(pos[tail_cnt]);
-
-    for (amt, delta) in input {
-        for _ in 0..*amt {
-            for j in 0..=tail_cnt {
-                let mut elem = pos[j];
-
-                if j == 0 {
-                    elem.apply_delta(delta);
-                } else {
-                    elem.move_toward(&pos[j - 1]);
-                }
-
-                pos[j] = elem;
-            }
-
-            tailpositions.insert(pos[tail_cnt]);
-        }
-    }
-
-    tailpositions.len()
-}
-
-type Delta = (isize, isize);
-
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
-struct Pos {
-    x: isize,
-    y: isize,
-}
-
-impl Pos {
-    fn apply_delta(&mut self, delta: &Delta) {
-        self.x += delta.0;
-        self.y += delta.1;
-    }
-
-    fn move_toward(&mut self, other: &Pos) {
-        let x_diff = other.x - self.x;
-        let y_diff = other.y - self.y;
-
-        let ax_diff = x_diff.abs();
-        let ay_diff = y_diff.abs();
-
-        if (ax_diff == 2 && ay_diff != 0) || (ay_diff == 2 && ax_diff != 0) {
-            // Diagonal move
-            self.x += x_diff.signum();
-            self.y += y_diff.signum();
-        } else if ax_diff == 2 {
-            // Straight move along x
-            self.x += x_diff.signum();
-        } else if ay_diff == 2 {
-            // Straight move along y
-            self.y += y_diff.signum();
-        }
-    }
-}
-
-// Input parsing
-
-type Instruction = (u32, Delta);
-
-fn input_transform(line: String) -> Instruction {
-    let split: Vec<&str> = line.split_whitespace().collect();
-
-    let amt = split[1].parse::<u32>().unwrap();
-
-    match split[0] {
-        "U" => (amt, (0, -1)),
-        "D" => (amt, (0, 1)),
-        "L" => (amt, (-1, 0)),
-        "R" => (amt, (1, 0)),
-        _ => panic!("Unknown instruction {line}"),
-    }
+    Rope::simulate(input, 9).tail_visited()
 }
 
 #[cfg(test)]
 mod tests {
     use aoc::input::parse_test_vec;
 
+    use day09lib::rope::Pos;
+
     use super::*;
 
     const EXAMPLE1: &str = "R 4
@@ -141,4 +68,44 @@ U 20
         let input = parse_test_vec(EXAMPLE2, input_transform).unwrap();
         assert_eq!(part2(&input), 36);
     }
+
+    #[test]
+    fn test3() {
+        let input = parse_test_vec(EXAMPLE1, input_transform).unwrap();
+        let rope = Rope::simulate(&input, 9);
+
+        // Start plus 24 steps for each knot
+        assert_eq!(rope.trajectories.len(), 10);
+        assert!(rope.trajectories.iter().all(|t| t.len() == 25));
+
+        // Head finishes at (2, -2), the first tail knot at (1, -2)
+        assert_eq!(rope.trajectories[0][24], Pos { x: 2, y: -2 });
+        assert_eq!(rope.trajectories[1][24], Pos { x: 1, y: -2 });
+
+        assert_eq!(rope.visited[1].len(), 13);
+        assert_eq!(rope.tail_visited(), 1);
+        assert_eq!(rope.bbox, (0, -4, 5, 0));
+    }
+
+    #[test]
+    fn test_diagonal() {
+        let input = parse_test_vec("UR 3\nDR 2\nL 1\n", input_transform).unwrap();
+        let rope = Rope::simulate(&input, 2);
+
+        assert_eq!(
+            rope.trajectories[0],
+            [(0, 0), (1, -1), (2, -2), (3, -3), (4, -2), (5, -1), (4, -1)]
+                .map(|(x, y)| Pos { x, y })
+        );
+        assert_eq!(
+            rope.trajectories[1],
+            [(0, 0), (0, 0), (1, -1), (2, -2), (3, -2), (4, -1), (4, -1)]
+                .map(|(x, y)| Pos { x, y })
+        );
+        assert_eq!(
+            rope.trajectories[2],
+            [(0, 0), (0, 0), (0, 0), (1, -1), (2, -2), (3, -1), (3, -1)].map(|(x, y)| Pos { x, y })
+        );
+        assert_eq!(rope.bbox, (0, -3, 5, 0));
+    }
 }
@@ -0,0 +1,112 @@
+use std::collections::HashSet;
+
+use crate::input::Instruction;
+
+/// Movement of the head of the rope for a single step
+pub type Delta = (isize, isize);
+
+/// Bounding box (min x, min y, max x, max y)
+pub type BBox = (isize, isize, isize, isize);
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Pos {
+    pub x: isize,
+    pub y: isize,
+}
+
+impl Pos {
+    pub fn apply_delta(&mut self, delta: &Delta) {
+        self.x += delta.0;
+        self.y += delta.1;
+    }
+
+    /// Moves this knot toward the knot in front of it if they are no longer touching
+    pub fn move_toward(&mut self, other: &Pos) {
+        let x_diff = other.x - self.x;
+        let y_diff = other.y - self.y;
+
+        let ax_diff = x_diff.abs();
+        let ay_diff = y_diff.abs();
+
+        if (ax_diff == 2 && ay_diff != 0) || (ay_diff == 2 && ax_diff != 0) {
+            // Diagonal move
+            self.x += x_diff.signum();
+            self.y += y_diff.signum();
+        } else if ax_diff == 2 {
+            // Straight move along x
+            self.x += x_diff.signum();
+        } else if ay_diff == 2 {
+            // Straight move along y
+            self.y += y_diff.signum();
+        }
+    }
+}
+
+/// Result of moving a rope
+pub struct Rope {
+    /// Position of each knot (head first) at each step, including the start
+    pub trajectories: Vec<Vec<Pos>>,
+    /// Cells visited by each knot (head first)
+    pub visited: Vec<HashSet<Pos>>,
+    /// Bounding box of all knot positions
+    pub bbox: BBox,
+}
+
+impl Rope {
+    /// Moves a rope with a head and a number of tail knots according to a list of
+    /// instructions, recording where every knot goes
+    pub fn simulate(input: &[Instruction], tail_cnt: usize) -> Self {
+        let knots = tail_cnt + 1;
+        let steps = input.iter().map(|(amt, _)| *amt as usize).sum::<usize>() + 1;
+
+        let mut pos = vec![Pos::default(); knots];
+        let mut trajectories = vec![Vec::with_capacity(steps); knots];
+        let mut visited = vec![HashSet::new(); knots];
+        let mut bbox = (0, 0, 0, 0);
+
+        let mut record = |pos: &[Pos]| {
+            for (j, elem) in pos.iter().enumerate() {
+                trajectories[j].push(*elem);
+                visited[j].insert(*elem);
+
+                bbox = (
+                    bbox.0.min(elem.x),
+                    bbox.1.min(elem.y),
+                    bbox.2.max(elem.x),
+                    bbox.3.max(elem.y),
+                );
+            }
+        };
+
+        record(&pos);
+
+        for (amt, delta) in input {
+            for _ in 0..*amt {
+                for j in 0..knots {
+                    let mut elem = pos[j];
+
+                    if j == 0 {
+                        elem.apply_delta(delta);
+                    } else {
+                        elem.move_toward(&pos[j - 1]);
+                    }
+
+                    pos[j] = elem;
+                }
+
+                record(&pos);
+            }
+        }
+
+        Self {
+            trajectories,
+            visited,
+            bbox,
+        }
+    }
+
+    /// Returns the number of cells visited by the last knot
+    pub fn tail_visited(&self) -> usize {
+        self.visited.last().map_or(0, |v| v.len())
+    }
+}
@@ -0,0 +1,124 @@
+use std::error::Error;
+
+use hsl::HSL;
+
+use aoc::gif::Gif;
+use aoc::input::parse_input_vec;
+
+use day09lib::input::{input_transform, Instruction};
+use day09lib::rope::Rope;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    // Get input
+    let input = parse_input_vec(9, input_transform)?;
+
+    // Create visualisations
+    println!("Generating visualisations...");
+    vis(&input, 1, "vis/day09-1-anim.gif")?;
+    vis(&input, 9, "vis/day09-2-anim.gif")?;
+
+    Ok(())
+}
+
+const BORDER: isize = 2;
+const MAX_GIF_SIZE: usize = 800;
+const MAX_FRAMES: usize = 2000;
+const FRAME_DELAY: u16 = 2;
+const FINAL_DELAY: u16 = 1000;
+
+const BG_COLOUR: u8 = 0;
+const TRAIL_BASE: u8 = 1; // Brightest trail colour
+const TRAIL_SHADES: u8 = 16;
+const FADE_STEPS: usize = 8; // Steps per trail shade
+const KNOT_BASE: u8 = TRAIL_BASE + TRAIL_SHADES; // Head first
+
+/// Builds the GIF colour palette for a number of knots
+fn palette(knots: usize) -> Vec<[u8; 3]> {
+    let hsl_rgb = |h: f64, s: f64, l: f64| {
+        let (r, g, b) = HSL { h, s, l }.to_rgb();
+        [r, g, b]
+    };
+
+    [[0x00, 0x00, 0x00]] // Background
+        .into_iter()
+        .chain((0..TRAIL_SHADES).map(|i| {
+            // Trail, fading from bright to dim blue
+            hsl_rgb(
+                220_f64,
+                0.5_f64,
+                0.6_f64 - (i as f64 * 0.4_f64) / TRAIL_SHADES as f64,
+            )
+        }))
+        .chain((0..knots).map(|i| {
+            // Knots, red (head) round the rainbow to violet (tail)
+            hsl_rgb((i as f64 * 300_f64) / knots as f64, 1_f64, 0.5_f64)
+        }))
+        .collect()
+}
+
+fn vis(input: &[Instruction], tail_cnt: usize, file: &str) -> Result<(), Box<dyn Error>> {
+    let rope = Rope::simulate(input, tail_cnt);
+    let knots = rope.trajectories.len();
+    let tail = knots - 1;
+    let steps = rope.trajectories[0].len();
+
+    // Fit the frame to the bounding box of all knot positions
+    let (min_x, min_y, max_x, max_y) = rope.bbox;
+    let (min_x, min_y) = (min_x - BORDER, min_y - BORDER);
+
+    let width = ((max_x + BORDER) - min_x) as usize + 1;
+    let height = ((max_y + BORDER) - min_y) as usize + 1;
+
+    let scale = (MAX_GIF_SIZE / width.max(height)).max(1) as u16;
+
+    let mut gif = Gif::new(
+        file,
+        &palette(knots),
+        width as u16,
+        height as u16,
+        scale,
+        scale,
+    )?;
+
+    // Step at which the tail last visited each cell
+    let mut last_visit: Vec<Vec<Option<usize>>> = vec![vec![None; width]; height];
+
+    let frame_every = (steps / MAX_FRAMES).max(1);
+
+    for step in 0..steps {
+        let tail_pos = rope.trajectories[tail][step];
+        last_visit[(tail_pos.y - min_y) as usize][(tail_pos.x - min_x) as usize] = Some(step);
+
+        if step % frame_every != 0 && step != steps - 1 {
+            continue;
+        }
+
+        // Draw the fading tail trail
+        let mut frame = last_visit
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|visit| match visit {
+                        None => BG_COLOUR,
+                        Some(v) => {
+                            TRAIL_BASE
+                                + ((step - v) / FADE_STEPS).min(TRAIL_SHADES as usize - 1) as u8
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        // Draw the knots, tail first so the head is on top
+        for knot in (0..knots).rev() {
+            let pos = rope.trajectories[knot][step];
+            frame[(pos.y - min_y) as usize][(pos.x - min_x) as usize] = KNOT_BASE + knot as u8;
+        }
+
+        gif.draw_frame(frame, FRAME_DELAY)?;
+    }
+
+    gif.delay(FINAL_DELAY)?;
+
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use std::error::Error;
+
+use crate::isa::{Instruction, Operand, REGISTER_NAMES};
+
+/// Assembles program source lines. Blank lines and comments starting with '#' are ignored.
+/// Errors are reported with the line number
+pub fn assemble<S>(lines: &[S]) -> Result<Vec<Instruction>, Box<dyn Error>>
+where
+    S: AsRef<str>,
+{
+    let mut program = Vec::with_capacity(lines.len());
+
+    for (i, line) in lines.iter().enumerate() {
+        if let Some(instruction) =
+            assemble_line(line.as_ref()).map_err(|e| format!("Line {}: {e}", i + 1))?
+        {
+            program.push(instruction);
+        }
+    }
+
+    Ok(program)
+}
+
+/// Assembles a single source line, returning None for a blank line or comment
+pub fn assemble_line(line: &str) -> Result<Option<Instruction>, String> {
+    // Strip comments
+    let line = match line.find('#') {
+        Some(pos) => &line[..pos],
+        None => line,
+    };
+
+    let mut split = line.split_whitespace();
+
+    let mnemonic = match split.next() {
+        Some(mnemonic) => mnemonic,
+        None => return Ok(None),
+    };
+
+    let args = split.map(parse_operand).collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Some(Instruction::new(mnemonic, args)?))
+}
+
+/// Disassembles a program to source lines
+pub fn disassemble(program: &[Instruction]) -> Vec<String> {
+    program.iter().map(|i| i.to_string()).collect()
+}
+
+/// Parses an operand as a register name or a number
+fn parse_operand(arg: &str) -> Result<Operand, String> {
+    if let Some(reg) = REGISTER_NAMES.iter().position(|r| *r == arg) {
+        Ok(Operand::Reg(reg))
+    } else {
+        arg.parse::<isize>()
+            .map(Operand::Imm)
+            .map_err(|_| format!("invalid operand '{arg}'"))
+    }
+}
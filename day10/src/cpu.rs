@@ -1,6 +1,8 @@
+use crate::isa::{Instruction, Registers, REGISTERS, X_REG};
+
 pub struct Cpu<'a> {
     instructions: &'a [Instruction],
-    x_reg: isize,
+    regs: Registers,
     pc: usize,
     cycles: usize,
     instruction_cycle: usize,
@@ -9,9 +11,12 @@ pub struct Cpu<'a> {
 
 impl<'a> Cpu<'a> {
     pub fn new(instructions: &'a [Instruction]) -> Self {
+        let mut regs = [0; REGISTERS];
+        regs[X_REG] = 1;
+
         Self {
             instructions,
-            x_reg: 1,
+            regs,
             pc: 0,
             cycles: 0,
             instruction_cycle: 0,
@@ -19,71 +24,78 @@ impl<'a> Cpu<'a> {
         }
     }
 
+    /// Moves on to the next cycle, returning false if the program has finished
     pub fn tick(&mut self) -> bool {
         self.cycles += 1;
 
         match self.cur_instruction {
             None => {
                 // Fetch first instruction
-                self.cur_instruction = Some(&self.instructions[self.pc]);
-                self.instruction_cycle = 1;
+                if !self.fetch() {
+                    return false;
+                }
             }
-            Some(instruction) => match instruction.tick(self) {
-                // Tick the instruction
-                InstructionAction::Retire => {
+            Some(instruction) => {
+                if self.instruction_cycle == instruction.cycles() {
+                    // Execute and retire the instruction
+                    let next_pc = match instruction.execute(&mut self.regs) {
+                        Some(offset) => self.pc.checked_add_signed(offset),
+                        None => Some(self.pc + 1),
+                    };
+
                     // Fetch the next instruction
-                    self.pc += 1;
+                    match next_pc {
+                        Some(pc) => self.pc = pc,
+                        None => return false,
+                    }
 
-                    if self.pc >= self.instructions.len() {
+                    if !self.fetch() {
                         return false;
                     }
-
-                    self.cur_instruction = Some(&self.instructions[self.pc]);
-                    self.instruction_cycle = 1;
+                } else {
+                    self.instruction_cycle += 1
                 }
-                InstructionAction::Executing => self.instruction_cycle += 1,
-            },
+            }
         }
 
         true
     }
 
     pub fn x_reg(&self) -> isize {
-        self.x_reg
+        self.regs[X_REG]
+    }
+
+    pub fn regs(&self) -> &Registers {
+        &self.regs
+    }
+
+    pub fn pc(&self) -> usize {
+        self.pc
     }
 
     pub fn cycles(&self) -> usize {
         self.cycles
     }
-}
 
-pub enum Instruction {
-    NoOp,
-    AddX(isize),
-}
+    /// Returns the instruction executing in the current cycle
+    pub fn instruction(&self) -> Option<&'a Instruction> {
+        self.cur_instruction
+    }
 
-impl Instruction {
-    fn tick(&self, cpu: &mut Cpu) -> InstructionAction {
-        use Instruction::*;
+    /// Returns which cycle of the current instruction is executing, starting at 1
+    pub fn instruction_cycle(&self) -> usize {
+        self.instruction_cycle
+    }
 
-        match self {
-            NoOp => match cpu.instruction_cycle {
-                1 => InstructionAction::Retire,
-                _ => unreachable!(),
-            },
-            AddX(amt) => match cpu.instruction_cycle {
-                1 => InstructionAction::Executing,
-                2 => {
-                    cpu.x_reg += amt;
-                    InstructionAction::Retire
-                }
-                _ => unreachable!(),
-            },
+    /// Fetches the instruction at the program counter, returning false if off the end of the program
+    fn fetch(&mut self) -> bool {
+        match self.instructions.get(self.pc) {
+            Some(instruction) => {
+                self.cur_instruction = Some(instruction);
+                self.instruction_cycle = 1;
+                true
+            }
+            None => false,
         }
     }
 }
-
-enum InstructionAction {
-    Retire,
-    Executing,
-}
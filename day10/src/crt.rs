@@ -0,0 +1,131 @@
+use crate::cpu::Cpu;
+use crate::isa::Instruction;
+
+/// Order in which the beam visits the pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanPattern {
+    /// Left to right, top to bottom
+    Raster,
+    /// Alternating left to right and right to left, top to bottom
+    Serpentine,
+    /// Even rows then odd rows, left to right
+    Interlaced,
+}
+
+/// CRT geometry
+#[derive(Debug, Clone)]
+pub struct CrtConfig {
+    pub width: usize,
+    pub height: usize,
+    pub sprite_width: usize,
+    pub scan: ScanPattern,
+}
+
+impl Default for CrtConfig {
+    fn default() -> Self {
+        Self {
+            width: 40,
+            height: 6,
+            sprite_width: 3,
+            scan: ScanPattern::Raster,
+        }
+    }
+}
+
+/// CRT screen drawn one pixel per cycle
+pub struct Crt {
+    config: CrtConfig,
+    pixels: Vec<Vec<bool>>,
+    drawn: usize,
+}
+
+impl Crt {
+    pub fn new(config: CrtConfig) -> Self {
+        let pixels = vec![vec![false; config.width]; config.height];
+
+        Self {
+            config,
+            pixels,
+            drawn: 0,
+        }
+    }
+
+    /// Returns the CRT geometry
+    pub fn config(&self) -> &CrtConfig {
+        &self.config
+    }
+
+    /// Returns the screen pixels
+    pub fn pixels(&self) -> &[Vec<bool>] {
+        &self.pixels
+    }
+
+    /// Returns the number of pixels drawn so far
+    pub fn drawn(&self) -> usize {
+        self.drawn
+    }
+
+    /// Returns true when every pixel has been drawn
+    pub fn full(&self) -> bool {
+        self.drawn == self.config.width * self.config.height
+    }
+
+    /// Returns the beam position (x, y) for a pixel index
+    pub fn beam(&self, index: usize) -> (usize, usize) {
+        let width = self.config.width;
+        let (x, y) = (index % width, index / width);
+
+        match self.config.scan {
+            ScanPattern::Raster => (x, y),
+            ScanPattern::Serpentine => {
+                if y % 2 == 0 {
+                    (x, y)
+                } else {
+                    (width - x - 1, y)
+                }
+            }
+            ScanPattern::Interlaced => {
+                let even_rows = self.config.height.div_ceil(2);
+
+                if y < even_rows {
+                    (x, y * 2)
+                } else {
+                    (x, ((y - even_rows) * 2) + 1)
+                }
+            }
+        }
+    }
+
+    /// Returns the columns covered by the sprite for a given X register value
+    pub fn sprite(&self, sprite_pos: isize) -> std::ops::Range<isize> {
+        let left = sprite_pos - ((self.config.sprite_width as isize - 1) / 2);
+
+        left..left + self.config.sprite_width as isize
+    }
+
+    /// Draws the next pixel with the sprite at a given position, returning the beam
+    /// position and whether the pixel was lit. Returns None if the screen is full
+    pub fn draw(&mut self, sprite_pos: isize) -> Option<((usize, usize), bool)> {
+        if self.full() {
+            return None;
+        }
+
+        let (x, y) = self.beam(self.drawn);
+        let lit = self.sprite(sprite_pos).contains(&(x as isize));
+
+        self.pixels[y][x] = lit;
+        self.drawn += 1;
+
+        Some(((x, y), lit))
+    }
+}
+
+/// Runs a program, drawing a pixel each cycle until the program finishes or the screen is full
+pub fn render(program: &[Instruction], config: CrtConfig) -> Vec<Vec<bool>> {
+    let mut cpu = Cpu::new(program);
+    let mut crt = Crt::new(config);
+
+    while cpu.tick() && crt.draw(cpu.x_reg()).is_some() {}
+
+    crt.pixels
+}
@@ -0,0 +1,190 @@
+use std::env;
+use std::error::Error;
+use std::fs::read_to_string;
+use std::io::{stdin, stdout, BufRead, Write};
+
+use aoc::input::parse_input_vec;
+
+use day10lib::asm::assemble;
+use day10lib::debugger::{Breakpoint, Debugger, Stop, Watchpoint};
+use day10lib::isa::REGISTER_NAMES;
+
+const HELP: &str = "Commands:
+  s [n]         Step n cycles (default 1)
+  c             Continue until a breakpoint, watchpoint or the end of the program
+  b cycle <n>   Break at the start of cycle n (after cycle 1)
+  b pc <n>      Break when the instruction at pc n starts
+  w             Watch for X changing
+  w <n>         Watch for X changing to n
+  d             Delete all breakpoints and watchpoints
+  i             Show CPU state, breakpoints and watchpoints
+  t [n]         Show the last n trace entries (default 10)
+  l             List the program around the program counter
+  h             Show this help
+  q             Quit";
+
+fn main() -> Result<(), Box<dyn Error>> {
+    // Get program from the file given or the puzzle input
+    let source = match env::args().nth(1) {
+        Some(file) => read_to_string(file)?
+            .lines()
+            .map(|l| l.to_string())
+            .collect(),
+        None => parse_input_vec(10, |line| line)?,
+    };
+
+    let program = assemble(&source)?;
+
+    let mut debugger = Debugger::new(&program);
+
+    println!("{} instructions loaded, 'h' for help", program.len());
+    show_state(&debugger);
+
+    let mut lines = stdin().lock().lines();
+
+    loop {
+        print!("> ");
+        stdout().flush()?;
+
+        let line = match lines.next() {
+            Some(line) => line?,
+            None => break,
+        };
+
+        let args = line.split_whitespace().collect::<Vec<_>>();
+
+        let number = |i: usize, default: usize| -> Result<usize, Box<dyn Error>> {
+            match args.get(i) {
+                Some(arg) => Ok(arg.parse::<usize>()?),
+                None => Ok(default),
+            }
+        };
+
+        let result: Result<(), Box<dyn Error>> = match args.first() {
+            None => Ok(()),
+            Some(&"s") => number(1, 1).map(|n| {
+                for _ in 0..n {
+                    if !debugger.running() {
+                        show_stop(&debugger, Stop::Halted);
+                        break;
+                    }
+
+                    let stop = debugger.step();
+
+                    if let Some(entry) = debugger.trace().back() {
+                        println!("{entry}");
+                    }
+
+                    if stop != Stop::Step {
+                        show_stop(&debugger, stop);
+                        break;
+                    }
+                }
+            }),
+            Some(&"c") => {
+                let stop = debugger.run();
+                show_stop(&debugger, stop);
+                Ok(())
+            }
+            Some(&"b") => match (args.get(1), number(2, 0)) {
+                (Some(&"cycle"), Ok(n)) if args.len() == 3 => debugger
+                    .add_breakpoint(Breakpoint::Cycle(n))
+                    .map_err(|e| e.into()),
+                (Some(&"pc"), Ok(n)) if args.len() == 3 => debugger
+                    .add_breakpoint(Breakpoint::Pc(n))
+                    .map_err(|e| e.into()),
+                _ => Err("Usage: b cycle <n> | b pc <n>".into()),
+            },
+            Some(&"w") => match args.get(1) {
+                None => {
+                    debugger.add_watchpoint(Watchpoint::Change);
+                    Ok(())
+                }
+                Some(arg) => arg.parse::<isize>().map_err(|e| e.into()).map(|n| {
+                    debugger.add_watchpoint(Watchpoint::Value(n));
+                }),
+            },
+            Some(&"d") => {
+                debugger.clear();
+                Ok(())
+            }
+            Some(&"i") => {
+                show_state(&debugger);
+                println!("Breakpoints: {:?}", debugger.breakpoints());
+                println!("Watchpoints: {:?}", debugger.watchpoints());
+                Ok(())
+            }
+            Some(&"t") => number(1, 10).map(|n| {
+                let trace = debugger.trace();
+
+                for entry in trace.range(trace.len().saturating_sub(n)..) {
+                    println!("{entry}");
+                }
+            }),
+            Some(&"l") => {
+                let pc = debugger.cpu().pc();
+
+                for (i, instruction) in program
+                    .iter()
+                    .enumerate()
+                    .skip(pc.saturating_sub(5))
+                    .take(11)
+                {
+                    let marker = if i == pc { "=>" } else { "  " };
+                    println!("{marker} {i:>5} {instruction}");
+                }
+
+                Ok(())
+            }
+            Some(&"h") => {
+                println!("{HELP}");
+                Ok(())
+            }
+            Some(&"q") => break,
+            Some(cmd) => Err(format!("Unknown command '{cmd}', 'h' for help").into()),
+        };
+
+        if let Err(e) = result {
+            println!("{e}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Shows why the program stopped
+fn show_stop(debugger: &Debugger, stop: Stop) {
+    match stop {
+        Stop::Step => (),
+        Stop::Breakpoint(b) => println!("Breakpoint: {b:?}"),
+        Stop::Watchpoint(w) => println!("Watchpoint: {w:?}"),
+        Stop::Halted => println!("Program finished"),
+    }
+
+    show_state(debugger);
+}
+
+/// Shows the state of the CPU at the start of the current cycle
+fn show_state(debugger: &Debugger) {
+    let cpu = debugger.cpu();
+
+    let regs = REGISTER_NAMES
+        .iter()
+        .zip(cpu.regs())
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    if debugger.running() {
+        println!(
+            "Cycle {} pc {} {} ({}/{}) {regs}",
+            cpu.cycles(),
+            cpu.pc(),
+            cpu.instruction().unwrap(),
+            cpu.instruction_cycle(),
+            cpu.instruction().unwrap().cycles()
+        );
+    } else {
+        println!("Finished after {} cycles {regs}", cpu.cycles() - 1);
+    }
+}
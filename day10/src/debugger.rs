@@ -0,0 +1,212 @@
+use std::collections::VecDeque;
+use std::fmt;
+
+use crate::cpu::Cpu;
+use crate::isa::Instruction;
+
+/// Maximum number of cycles kept in the trace
+pub const TRACE_LIMIT: usize = 100_000;
+
+/// Condition which stops the program before a cycle starts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Breakpoint {
+    /// Stop at a given cycle number after the first
+    Cycle(usize),
+    /// Stop when the instruction at a given program counter starts
+    Pc(usize),
+}
+
+/// Condition on the X register which stops the program after a cycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Watchpoint {
+    /// Stop when X changes
+    Change,
+    /// Stop when X changes to a given value
+    Value(isize),
+}
+
+/// Why the program stopped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stop {
+    Step,
+    Breakpoint(Breakpoint),
+    Watchpoint(Watchpoint),
+    Halted,
+}
+
+/// Record of a single cycle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceEntry<'a> {
+    pub cycle: usize,
+    pub pc: usize,
+    pub instruction: &'a Instruction,
+    /// Which cycle of the instruction this was, starting at 1
+    pub instruction_cycle: usize,
+    /// Value of X during the cycle
+    pub x_before: isize,
+    /// Value of X after the cycle
+    pub x_after: isize,
+}
+
+impl fmt::Display for TraceEntry<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:>6} {:>5} {:<16} {}/{} x={}",
+            self.cycle,
+            self.pc,
+            self.instruction.to_string(),
+            self.instruction_cycle,
+            self.instruction.cycles(),
+            self.x_before
+        )?;
+
+        if self.x_after != self.x_before {
+            write!(f, " -> {}", self.x_after)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Steps a CPU one cycle at a time with breakpoints, watchpoints and a trace of the most
+/// recent cycles. Between steps the CPU is always positioned at the start of a cycle
+pub struct Debugger<'a> {
+    cpu: Cpu<'a>,
+    running: bool,
+    breakpoints: Vec<Breakpoint>,
+    watchpoints: Vec<Watchpoint>,
+    trace: VecDeque<TraceEntry<'a>>,
+}
+
+impl<'a> Debugger<'a> {
+    /// Creates a debugger positioned at the start of the first cycle
+    pub fn new(instructions: &'a [Instruction]) -> Self {
+        let mut cpu = Cpu::new(instructions);
+        let running = cpu.tick();
+
+        Self {
+            cpu,
+            running,
+            breakpoints: Vec::new(),
+            watchpoints: Vec::new(),
+            trace: VecDeque::new(),
+        }
+    }
+
+    /// Returns the CPU
+    pub fn cpu(&self) -> &Cpu<'a> {
+        &self.cpu
+    }
+
+    /// Returns true if the program is still running
+    pub fn running(&self) -> bool {
+        self.running
+    }
+
+    /// Returns the trace of the last `TRACE_LIMIT` cycles executed
+    pub fn trace(&self) -> &VecDeque<TraceEntry<'a>> {
+        &self.trace
+    }
+
+    /// Returns the breakpoints
+    pub fn breakpoints(&self) -> &[Breakpoint] {
+        &self.breakpoints
+    }
+
+    /// Returns the watchpoints
+    pub fn watchpoints(&self) -> &[Watchpoint] {
+        &self.watchpoints
+    }
+
+    /// Adds a breakpoint. Breakpoints are checked between cycles so the first cycle
+    /// can't be broken at
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) -> Result<(), String> {
+        if let Breakpoint::Cycle(0 | 1) = breakpoint {
+            Err("Cycle breakpoints must be after cycle 1")?
+        }
+
+        self.breakpoints.push(breakpoint);
+
+        Ok(())
+    }
+
+    /// Adds a watchpoint
+    pub fn add_watchpoint(&mut self, watchpoint: Watchpoint) {
+        self.watchpoints.push(watchpoint);
+    }
+
+    /// Removes all breakpoints and watchpoints
+    pub fn clear(&mut self) {
+        self.breakpoints.clear();
+        self.watchpoints.clear();
+    }
+
+    /// Executes a single cycle
+    pub fn step(&mut self) -> Stop {
+        if !self.running {
+            return Stop::Halted;
+        }
+
+        let instruction = self
+            .cpu
+            .instruction()
+            .expect("No instruction during a cycle");
+
+        let cycle = self.cpu.cycles();
+        let pc = self.cpu.pc();
+        let instruction_cycle = self.cpu.instruction_cycle();
+        let x_before = self.cpu.x_reg();
+
+        self.running = self.cpu.tick();
+
+        let x_after = self.cpu.x_reg();
+
+        if self.trace.len() == TRACE_LIMIT {
+            self.trace.pop_front();
+        }
+
+        self.trace.push_back(TraceEntry {
+            cycle,
+            pc,
+            instruction,
+            instruction_cycle,
+            x_before,
+            x_after,
+        });
+
+        // Check watchpoints
+        if x_after != x_before {
+            if let Some(watchpoint) = self.watchpoints.iter().find(|w| match w {
+                Watchpoint::Change => true,
+                Watchpoint::Value(v) => *v == x_after,
+            }) {
+                return Stop::Watchpoint(*watchpoint);
+            }
+        }
+
+        if !self.running {
+            return Stop::Halted;
+        }
+
+        // Check breakpoints
+        if let Some(breakpoint) = self.breakpoints.iter().find(|b| match b {
+            Breakpoint::Cycle(c) => *c == self.cpu.cycles(),
+            Breakpoint::Pc(pc) => *pc == self.cpu.pc() && self.cpu.instruction_cycle() == 1,
+        }) {
+            return Stop::Breakpoint(*breakpoint);
+        }
+
+        Stop::Step
+    }
+
+    /// Executes cycles until a breakpoint or watchpoint is hit or the program halts
+    pub fn run(&mut self) -> Stop {
+        loop {
+            match self.step() {
+                Stop::Step => (),
+                stop => break stop,
+            }
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use std::fmt;
+
+/// Number of registers
+pub const REGISTERS: usize = 4;
+
+/// Register names. X is the register used by the CRT
+pub const REGISTER_NAMES: [&str; REGISTERS] = ["x", "y", "z", "w"];
+
+/// Index of the X register
+pub const X_REG: usize = 0;
+
+/// Register file
+pub type Registers = [isize; REGISTERS];
+
+/// Instruction operand
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operand {
+    Reg(usize),
+    Imm(isize),
+}
+
+impl Operand {
+    /// Returns the value of the operand
+    fn value(&self, regs: &Registers) -> isize {
+        match self {
+            Operand::Reg(r) => regs[*r],
+            Operand::Imm(v) => *v,
+        }
+    }
+
+    /// Returns the register number of the operand, or None for an immediate value
+    pub fn reg(&self) -> Option<usize> {
+        match self {
+            Operand::Reg(r) => Some(*r),
+            Operand::Imm(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operand::Reg(r) => write!(f, "{}", REGISTER_NAMES[*r]),
+            Operand::Imm(v) => write!(f, "{v}"),
+        }
+    }
+}
+
+/// Kinds of operand an instruction accepts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperandKind {
+    /// Register
+    Reg,
+    /// Immediate value
+    Imm,
+    /// Register or immediate value
+    Src,
+}
+
+/// Instruction definition
+pub struct OpDef {
+    /// Assembler mnemonic
+    pub mnemonic: &'static str,
+    /// Operands accepted
+    pub operands: &'static [OperandKind],
+    /// Number of cycles taken to execute
+    pub cycles: usize,
+    /// Executes the instruction when it retires, returning a relative jump for the program counter.
+    /// Register operands have been checked when the instruction was created and arithmetic wraps
+    exec: fn(&mut Registers, &[Operand]) -> Option<isize>,
+}
+
+use OperandKind::{Imm, Reg, Src};
+
+/// The instruction set
+pub const INSTRUCTION_SET: &[OpDef] = &[
+    OpDef {
+        mnemonic: "noop",
+        operands: &[],
+        cycles: 1,
+        exec: |_, _| None,
+    },
+    OpDef {
+        mnemonic: "addx",
+        operands: &[Imm],
+        cycles: 2,
+        exec: |regs, args| {
+            regs[X_REG] = regs[X_REG].wrapping_add(args[0].value(regs));
+            None
+        },
+    },
+    OpDef {
+        mnemonic: "set",
+        operands: &[Reg, Src],
+        cycles: 1,
+        exec: |regs, args| {
+            if let Some(r) = args[0].reg() {
+                regs[r] = args[1].value(regs);
+            }
+            None
+        },
+    },
+    OpDef {
+        mnemonic: "add",
+        operands: &[Reg, Src],
+        cycles: 2,
+        exec: |regs, args| {
+            if let Some(r) = args[0].reg() {
+                regs[r] = regs[r].wrapping_add(args[1].value(regs));
+            }
+            None
+        },
+    },
+    OpDef {
+        mnemonic: "sub",
+        operands: &[Reg, Src],
+        cycles: 2,
+        exec: |regs, args| {
+            if let Some(r) = args[0].reg() {
+                regs[r] = regs[r].wrapping_sub(args[1].value(regs));
+            }
+            None
+        },
+    },
+    OpDef {
+        mnemonic: "mul",
+        operands: &[Reg, Src],
+        cycles: 4,
+        exec: |regs, args| {
+            if let Some(r) = args[0].reg() {
+                regs[r] = regs[r].wrapping_mul(args[1].value(regs));
+            }
+            None
+        },
+    },
+    OpDef {
+        mnemonic: "jnz",
+        operands: &[Src, Imm],
+        cycles: 2,
+        exec: |regs, args| {
+            if args[0].value(regs) != 0 {
+                Some(args[1].value(regs))
+            } else {
+                None
+            }
+        },
+    },
+];
+
+/// Instruction with operands
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    opcode: usize,
+    args: Vec<Operand>,
+}
+
+impl Instruction {
+    /// Creates a new instruction, checking the operands against the instruction definition
+    pub fn new(mnemonic: &str, args: Vec<Operand>) -> Result<Self, String> {
+        let opcode = INSTRUCTION_SET
+            .iter()
+            .position(|def| def.mnemonic == mnemonic)
+            .ok_or(format!("unknown instruction '{mnemonic}'"))?;
+
+        let def = &INSTRUCTION_SET[opcode];
+
+        if args.len() != def.operands.len() {
+            Err(format!(
+                "'{mnemonic}' expects {} operand(s), found {}",
+                def.operands.len(),
+                args.len()
+            ))?
+        }
+
+        for (i, (arg, kind)) in args.iter().zip(def.operands).enumerate() {
+            match (kind, arg) {
+                (Reg, Operand::Imm(_)) => {
+                    Err(format!("'{mnemonic}' operand {} must be a register", i + 1))?
+                }
+                (Imm, Operand::Reg(_)) => {
+                    Err(format!("'{mnemonic}' operand {} must be a number", i + 1))?
+                }
+                _ => (),
+            }
+        }
+
+        Ok(Self { opcode, args })
+    }
+
+    /// Returns the instruction definition
+    pub fn def(&self) -> &'static OpDef {
+        &INSTRUCTION_SET[self.opcode]
+    }
+
+    /// Returns the number of cycles the instruction takes
+    pub fn cycles(&self) -> usize {
+        self.def().cycles
+    }
+
+    /// Returns the instruction operands
+    pub fn args(&self) -> &[Operand] {
+        &self.args
+    }
+
+    /// Executes the instruction, returning a relative jump for the program counter
+    pub fn execute(&self, regs: &mut Registers) -> Option<isize> {
+        (self.def().exec)(regs, &self.args)
+    }
+}
+
+/// Renders the instruction in assembler syntax
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.def().mnemonic)?;
+
+        for arg in &self.args {
+            write!(f, " {arg}")?;
+        }
+
+        Ok(())
+    }
+}
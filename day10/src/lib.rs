@@ -0,0 +1,6 @@
+pub mod asm;
+pub mod cpu;
+pub mod crt;
+pub mod debugger;
+pub mod isa;
+pub mod ocr;
@@ -1,29 +1,22 @@
 use std::error::Error;
 
 use aoc::input::parse_input_vec;
-use cpu::Cpu;
 
-mod cpu;
-
-use crate::cpu::Instruction;
+use day10lib::{
+    asm::assemble,
+    cpu::Cpu,
+    crt::{render, CrtConfig},
+    isa::Instruction,
+    ocr::decode,
+};
 
 fn main() -> Result<(), Box<dyn Error>> {
     // Get input
-    let input = parse_input_vec(10, input_transform)?;
+    let input = assemble(&parse_input_vec(10, input_transform)?)?;
 
     // Run parts
     println!("Part 1: {}", part1(&input));
-
-    println!("Part 2:");
-
-    for line in part2(&input) {
-        println!(
-            "  {}",
-            line.into_iter()
-                .map(|c| if c { '█' } else { ' ' })
-                .collect::<String>()
-        );
-    }
+    println!("Part 2: {}", part2(&input)?);
 
     Ok(())
 }
@@ -41,55 +34,24 @@ fn part1(input: &[Instruction]) -> isize {
     strength
 }
 
-const SCREEN_WIDTH: usize = 40;
-const SCREEN_HEIGHT: usize = 6;
-
-fn part2(input: &[Instruction]) -> Vec<Vec<bool>> {
-    let mut output = Vec::with_capacity(SCREEN_HEIGHT);
-    let mut cur_line = Vec::with_capacity(SCREEN_WIDTH);
-    let mut cpu = Cpu::new(input);
-
-    while cpu.tick() {
-        let sprite_pos = cpu.x_reg();
-        let x = cur_line.len() as isize;
-
-        cur_line.push(x >= sprite_pos - 1 && x <= sprite_pos + 1);
-
-        if x == SCREEN_WIDTH as isize - 1 {
-            output.push(cur_line);
-            cur_line = Vec::with_capacity(SCREEN_WIDTH);
-        }
-    }
-
-    if !cur_line.is_empty() {
-        output.push(cur_line);
-    }
-
-    output
+fn part2(input: &[Instruction]) -> Result<String, Box<dyn Error>> {
+    decode(&render(input, CrtConfig::default()))
 }
 
 // Input parsing
 
-fn input_transform(line: String) -> Instruction {
-    let mut split = line.split_whitespace();
-
-    match split.next() {
-        Some("noop") => Instruction::NoOp,
-        Some("addx") => Instruction::AddX(
-            split
-                .next()
-                .expect("addx needs an argument")
-                .parse()
-                .expect("addx argument not numeric"),
-        ),
-        _ => panic!("Unknown instruction {line}"),
-    }
+fn input_transform(line: String) -> String {
+    line
 }
 
 #[cfg(test)]
 mod tests {
     use aoc::input::parse_test_input_vec;
 
+    use day10lib::asm::disassemble;
+    use day10lib::crt::{Crt, ScanPattern};
+    use day10lib::debugger::{Breakpoint, Debugger, Stop, Watchpoint, TRACE_LIMIT};
+
     use super::*;
 
     const EXAMPLE_RESULT: [&str; 6] = [
@@ -103,10 +65,10 @@ mod tests {
 
     #[test]
     fn test1() {
-        let input = parse_test_input_vec(10, 1, input_transform).unwrap();
+        let input = assemble(&parse_test_input_vec(10, 1, input_transform).unwrap()).unwrap();
         assert_eq!(part1(&input), 13140);
         assert_eq!(
-            part2(&input)
+            render(&input, CrtConfig::default())
                 .into_iter()
                 .map(|l| l
                     .into_iter()
@@ -116,4 +78,224 @@ mod tests {
             EXAMPLE_RESULT
         );
     }
+
+    #[test]
+    fn test_crt_config() {
+        let input = assemble(&parse_test_input_vec(10, 1, input_transform).unwrap()).unwrap();
+
+        let to_strings = |pixels: Vec<Vec<bool>>| {
+            pixels
+                .into_iter()
+                .map(|l| {
+                    l.into_iter()
+                        .map(|p| if p { '#' } else { '.' })
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // Narrow screen with a wide sprite
+        let config = CrtConfig {
+            width: 8,
+            height: 3,
+            sprite_width: 5,
+            scan: ScanPattern::Raster,
+        };
+
+        assert_eq!(
+            to_strings(render(&input, config.clone())),
+            ["##..##..", "......#.", "........"]
+        );
+
+        // Serpentine scan draws odd rows right to left
+        let pixels = render(
+            &input,
+            CrtConfig {
+                scan: ScanPattern::Serpentine,
+                ..config.clone()
+            },
+        );
+        assert_eq!(to_strings(pixels)[1], "......##");
+
+        // Interlaced scan draws even rows first
+        let crt = Crt::new(CrtConfig {
+            scan: ScanPattern::Interlaced,
+            ..config
+        });
+        assert_eq!(
+            (0..24).step_by(8).map(|i| crt.beam(i)).collect::<Vec<_>>(),
+            [(0, 0), (0, 2), (0, 1)]
+        );
+        assert_eq!(crt.sprite(1), -1..4);
+    }
+
+    #[test]
+    fn test_ocr() {
+        let screen = |rows: [&str; 6]| {
+            rows.iter()
+                .map(|r| r.chars().map(|c| c == '#').collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            decode(&screen([
+                "###..#..#.####.###..",
+                "#..#.#..#....#.#..#.",
+                "#..#.#..#...#..#..#.",
+                "###..#..#..#...###..",
+                "#.#..#..#.#....#.#..",
+                "#..#..##..####.#..#.",
+            ]))
+            .unwrap(),
+            "RUZR"
+        );
+
+        let error = decode(&screen([
+            "####.#..#",
+            "#....#..#",
+            "###..####",
+            "#....#..#",
+            "#....#..#",
+            "####.#.##",
+        ]))
+        .unwrap_err()
+        .to_string();
+        assert_eq!(
+            error,
+            "Unrecognised glyph at position 2:\n#..#\n#..#\n####\n#..#\n#..#\n#.##"
+        );
+
+        assert!(decode(&screen(["#", "#", "#", "#", "#", "#"])[..5]).is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let source = parse_test_input_vec(10, 1, input_transform).unwrap();
+        let program = assemble(&source).unwrap();
+
+        // Disassembly should match the source
+        let disassembly = disassemble(&program);
+        assert_eq!(disassembly, source);
+        assert_eq!(assemble(&disassembly).unwrap(), program);
+
+        // Program using the extended instruction set
+        let source = [
+            "set y 3 # loop counter",
+            "",
+            "mul x 2",
+            "sub y 1",
+            "jnz y -2",
+        ];
+        let program = assemble(&source).unwrap();
+        assert_eq!(
+            disassemble(&program),
+            ["set y 3", "mul x 2", "sub y 1", "jnz y -2"]
+        );
+        assert_eq!(assemble(&disassemble(&program)).unwrap(), program);
+
+        // Run it
+        let mut cpu = Cpu::new(&program);
+        while cpu.tick() {}
+        assert_eq!(cpu.x_reg(), 8);
+        assert_eq!(cpu.regs()[1], 0);
+        assert_eq!(cpu.cycles(), 1 + 3 * (4 + 2 + 2) + 1);
+
+        // Register arithmetic wraps on overflow
+        let program = assemble(&[
+            "set y 9223372036854775807",
+            "add y 1",
+            "set z y",
+            "sub z 1",
+            "mul y 2",
+            "set x z",
+            "addx 1",
+        ])
+        .unwrap();
+        let mut cpu = Cpu::new(&program);
+        while cpu.tick() {}
+        assert_eq!(cpu.regs(), &[isize::MIN, 0, isize::MAX, 0]);
+    }
+
+    #[test]
+    fn test_debugger() {
+        let input = assemble(&parse_test_input_vec(10, 1, input_transform).unwrap()).unwrap();
+        let mut debugger = Debugger::new(&input);
+
+        // Signal strength sampling points
+        for cycle in [20, 60, 100, 140, 180, 220] {
+            debugger.add_breakpoint(Breakpoint::Cycle(cycle)).unwrap();
+        }
+
+        let mut samples = Vec::new();
+
+        while let Stop::Breakpoint(Breakpoint::Cycle(cycle)) = debugger.run() {
+            assert_eq!(debugger.cpu().cycles(), cycle);
+            samples.push(debugger.cpu().x_reg());
+        }
+
+        assert_eq!(samples, [21, 19, 18, 21, 16, 18]);
+
+        // Every cycle is traced
+        assert!(!debugger.running());
+        assert_eq!(debugger.trace().len(), 240);
+
+        let first = debugger.trace().range(..3).collect::<Vec<_>>();
+        assert_eq!(first[0].instruction.to_string(), "addx 15");
+        assert_eq!((first[0].x_before, first[0].x_after), (1, 1));
+        assert_eq!((first[1].x_before, first[1].x_after), (1, 16));
+        assert_eq!((first[2].pc, first[2].x_before), (1, 16));
+
+        // Watch for X changing to -1 and break at the start of an instruction
+        let mut debugger = Debugger::new(&input);
+        debugger.add_watchpoint(Watchpoint::Value(-1));
+        debugger.add_breakpoint(Breakpoint::Pc(10)).unwrap();
+
+        assert_eq!(debugger.run(), Stop::Breakpoint(Breakpoint::Pc(10)));
+        assert_eq!(debugger.cpu().instruction_cycle(), 1);
+        assert_eq!(debugger.run(), Stop::Watchpoint(Watchpoint::Value(-1)));
+        assert_eq!(debugger.cpu().x_reg(), -1);
+
+        // The first cycle has started before a breakpoint can be checked
+        assert!(debugger.add_breakpoint(Breakpoint::Cycle(1)).is_err());
+
+        // An empty program has already finished
+        let mut debugger = Debugger::new(&[]);
+        assert!(!debugger.running());
+        assert_eq!(debugger.step(), Stop::Halted);
+        assert!(debugger.trace().is_empty());
+
+        // The trace only keeps the most recent cycles of a program which never ends
+        let program = assemble(&["jnz 1 0"]).unwrap();
+        let mut debugger = Debugger::new(&program);
+
+        for _ in 0..TRACE_LIMIT + 10 {
+            assert_eq!(debugger.step(), Stop::Step);
+        }
+
+        assert_eq!(debugger.trace().len(), TRACE_LIMIT);
+        assert_eq!(debugger.trace()[0].cycle, 11);
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        let error = |source: &[&str]| assemble(source).unwrap_err().to_string();
+
+        assert_eq!(
+            error(&["noop", "addx"]),
+            "Line 2: 'addx' expects 1 operand(s), found 0"
+        );
+        assert_eq!(error(&["jmp 1"]), "Line 1: unknown instruction 'jmp'");
+        assert_eq!(
+            error(&["noop", "", "addx q"]),
+            "Line 3: invalid operand 'q'"
+        );
+        assert_eq!(
+            error(&["addx y"]),
+            "Line 1: 'addx' operand 1 must be a number"
+        );
+        assert_eq!(
+            error(&["set 1 2"]),
+            "Line 1: 'set' operand 1 must be a register"
+        );
+    }
 }
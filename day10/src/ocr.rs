@@ -0,0 +1,74 @@
+use std::error::Error;
+
+/// Width of a glyph in pixels
+pub const GLYPH_WIDTH: usize = 4;
+
+/// Height of a glyph in pixels
+pub const GLYPH_HEIGHT: usize = 6;
+
+/// Horizontal distance between the start of each glyph
+const GLYPH_PITCH: usize = GLYPH_WIDTH + 1;
+
+/// Known glyphs, one string per row
+const GLYPHS: [(char, [&str; GLYPH_HEIGHT]); 18] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...", "#...", ".#.#", "..#.", "..#.", "..#."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+/// Decodes a screen of glyphs in to a string of capital letters
+pub fn decode(screen: &[Vec<bool>]) -> Result<String, Box<dyn Error>> {
+    if screen.len() != GLYPH_HEIGHT {
+        Err(format!(
+            "Screen height is {}, expected {GLYPH_HEIGHT}",
+            screen.len()
+        ))?
+    }
+
+    let width = screen.iter().map(|row| row.len()).min().unwrap_or(0);
+
+    (0..(width + 1) / GLYPH_PITCH)
+        .map(|i| {
+            let x = i * GLYPH_PITCH;
+
+            // Render the glyph in the same format as the glyph table
+            let glyph = screen
+                .iter()
+                .map(|row| {
+                    row[x..x + GLYPH_WIDTH]
+                        .iter()
+                        .map(|p| if *p { '#' } else { '.' })
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>();
+
+            GLYPHS
+                .iter()
+                .find(|(_, rows)| rows.iter().zip(glyph.iter()).all(|(a, b)| a == b))
+                .map(|(c, _)| *c)
+                .ok_or_else(|| {
+                    format!(
+                        "Unrecognised glyph at position {}:\n{}",
+                        i + 1,
+                        glyph.join("\n")
+                    )
+                    .into()
+                })
+        })
+        .collect()
+}
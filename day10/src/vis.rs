@@ -0,0 +1,95 @@
+use std::error::Error;
+
+use aoc::gif::{Gif, IdenticalAction};
+use aoc::input::parse_input_vec;
+
+use day10lib::asm::assemble;
+use day10lib::cpu::Cpu;
+use day10lib::crt::{Crt, CrtConfig};
+use day10lib::isa::Instruction;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    // Get input
+    let input = assemble(&parse_input_vec(10, |line| line)?)?;
+
+    // Create visualisation
+    println!("Generating visualisation...");
+    vis(&input, CrtConfig::default(), "vis/day10-anim.gif")?;
+
+    Ok(())
+}
+
+const SCALE: u16 = 16;
+const FRAME_DELAY: u16 = 4;
+const FINAL_DELAY: u16 = 1000;
+
+const UNDRAWN_COLOUR: u8 = 0; // Black
+const DARK_COLOUR: u8 = 1; // Dark green
+const LIT_COLOUR: u8 = 2; // Bright green
+const SPRITE_COLOUR: u8 = 3; // Dark blue
+const SPRITE_LIT_COLOUR: u8 = 4; // Pale blue
+const BEAM_COLOUR: u8 = 5; // Red
+
+const COLOUR_PALETTE: [[u8; 3]; 6] = [
+    [0x00, 0x00, 0x00],
+    [0x10, 0x30, 0x10],
+    [0x40, 0xff, 0x40],
+    [0x20, 0x30, 0x80],
+    [0x80, 0xc0, 0xff],
+    [0xff, 0x20, 0x20],
+];
+
+fn vis(input: &[Instruction], config: CrtConfig, file: &str) -> Result<(), Box<dyn Error>> {
+    let mut cpu = Cpu::new(input);
+    let mut crt = Crt::new(config);
+
+    let width = crt.config().width;
+    let height = crt.config().height;
+
+    let mut gif = Gif::new(
+        file,
+        &COLOUR_PALETTE,
+        width as u16,
+        height as u16,
+        SCALE,
+        SCALE,
+    )?;
+
+    let mut screen = vec![vec![UNDRAWN_COLOUR; width]; height];
+
+    while cpu.tick() {
+        let sprite_pos = cpu.x_reg();
+
+        // Draw the next pixel
+        let ((bx, by), lit) = match crt.draw(sprite_pos) {
+            Some(pixel) => pixel,
+            None => break,
+        };
+
+        screen[by][bx] = if lit { LIT_COLOUR } else { DARK_COLOUR };
+
+        // Overlay the sprite on the beam row and draw the beam
+        let mut frame = screen.clone();
+
+        for x in crt.sprite(sprite_pos) {
+            if x >= 0 && (x as usize) < width {
+                let pixel = &mut frame[by][x as usize];
+
+                *pixel = if *pixel == LIT_COLOUR {
+                    SPRITE_LIT_COLOUR
+                } else {
+                    SPRITE_COLOUR
+                };
+            }
+        }
+
+        frame[by][bx] = BEAM_COLOUR;
+
+        gif.draw_frame(frame, FRAME_DELAY)?;
+    }
+
+    // Show the finished screen
+    gif.draw_frame_identical_check(screen, FINAL_DELAY, IdenticalAction::Delay)?;
+
+    Ok(())
+}
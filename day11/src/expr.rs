@@ -0,0 +1,200 @@
+use std::fmt;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// Arithmetic on worry levels. Operations return None if the result can't be represented
+pub trait Arith: Clone {
+    /// Returns a constant in the same representation
+    fn constant(&self, value: usize) -> Self;
+    fn add(&self, rhs: &Self) -> Option<Self>;
+    fn sub(&self, rhs: &Self) -> Option<Self>;
+    fn mul(&self, rhs: &Self) -> Option<Self>;
+    /// Returns true if divisible by a divisor
+    fn divisible(&self, divisor: usize) -> bool;
+}
+
+impl Arith for usize {
+    fn constant(&self, value: usize) -> Self {
+        value
+    }
+
+    fn add(&self, rhs: &Self) -> Option<Self> {
+        self.checked_add(*rhs)
+    }
+
+    fn sub(&self, rhs: &Self) -> Option<Self> {
+        self.checked_sub(*rhs)
+    }
+
+    fn mul(&self, rhs: &Self) -> Option<Self> {
+        self.checked_mul(*rhs)
+    }
+
+    fn divisible(&self, divisor: usize) -> bool {
+        self.is_multiple_of(divisor)
+    }
+}
+
+/// Monkey operation expression
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum Expr {
+    #[default]
+    Old,
+    Num(usize),
+    Add(Box<Expr>, Box<Expr>),
+    Sub(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    /// Parses an expression made up of 'old', numbers, +, -, * and parentheses
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let mut chars = s.chars().peekable();
+
+        let expr = Self::parse_sum(&mut chars)?;
+
+        match next_token(&mut chars) {
+            None => Ok(expr),
+            Some(c) => Err(format!("unexpected '{c}'")),
+        }
+    }
+
+    /// Evaluates the expression for an old worry level. Fails if part of the
+    /// expression goes out of range
+    pub fn eval<T: Arith>(&self, old: &T) -> Result<T, String> {
+        let result = match self {
+            Expr::Old => return Ok(old.clone()),
+            Expr::Num(n) => return Ok(old.constant(*n)),
+            Expr::Add(l, r) => l.eval(old)?.add(&r.eval(old)?),
+            Expr::Sub(l, r) => l.eval(old)?.sub(&r.eval(old)?),
+            Expr::Mul(l, r) => l.eval(old)?.mul(&r.eval(old)?),
+        };
+
+        result.ok_or(format!("'{self}' is out of range"))
+    }
+
+    /// sum := product (('+' | '-') product)*
+    fn parse_sum(chars: &mut Peekable<Chars>) -> Result<Self, String> {
+        let mut expr = Self::parse_product(chars)?;
+
+        loop {
+            match peek_token(chars) {
+                Some('+') => {
+                    chars.next();
+                    expr = Expr::Add(Box::new(expr), Box::new(Self::parse_product(chars)?));
+                }
+                Some('-') => {
+                    chars.next();
+                    expr = Expr::Sub(Box::new(expr), Box::new(Self::parse_product(chars)?));
+                }
+                _ => break Ok(expr),
+            }
+        }
+    }
+
+    /// product := term ('*' term)*
+    fn parse_product(chars: &mut Peekable<Chars>) -> Result<Self, String> {
+        let mut expr = Self::parse_term(chars)?;
+
+        while peek_token(chars) == Some('*') {
+            chars.next();
+            expr = Expr::Mul(Box::new(expr), Box::new(Self::parse_term(chars)?));
+        }
+
+        Ok(expr)
+    }
+
+    /// term := 'old' | number | '(' sum ')'
+    fn parse_term(chars: &mut Peekable<Chars>) -> Result<Self, String> {
+        match next_token(chars) {
+            Some('(') => {
+                let expr = Self::parse_sum(chars)?;
+
+                match next_token(chars) {
+                    Some(')') => Ok(expr),
+                    Some(c) => Err(format!("expected ')', found '{c}'")),
+                    None => Err("expected ')'".to_string()),
+                }
+            }
+            Some(c) if c.is_ascii_digit() => {
+                let mut num = c.to_digit(10).unwrap() as usize;
+
+                while let Some(d) = chars.peek().and_then(|c| c.to_digit(10)) {
+                    num = num
+                        .checked_mul(10)
+                        .and_then(|n| n.checked_add(d as usize))
+                        .ok_or("number too large")?;
+                    chars.next();
+                }
+
+                Ok(Expr::Num(num))
+            }
+            Some(c) if c.is_ascii_alphabetic() => {
+                let mut word = c.to_string();
+
+                while let Some(c) = chars.next_if(|c| c.is_ascii_alphanumeric()) {
+                    word.push(c);
+                }
+
+                match word.as_str() {
+                    "old" => Ok(Expr::Old),
+                    _ => Err(format!("unknown variable '{word}'")),
+                }
+            }
+            Some(c) => Err(format!("unexpected '{c}'")),
+            None => Err("unexpected end of expression".to_string()),
+        }
+    }
+
+    /// Binding strength of the top level operator
+    fn precedence(&self) -> u8 {
+        match self {
+            Expr::Old | Expr::Num(_) => 3,
+            Expr::Mul(_, _) => 2,
+            Expr::Add(_, _) | Expr::Sub(_, _) => 1,
+        }
+    }
+}
+
+/// Skips whitespace and returns the next character
+fn next_token(chars: &mut Peekable<Chars>) -> Option<char> {
+    peek_token(chars);
+    chars.next()
+}
+
+/// Skips whitespace and peeks at the next character
+fn peek_token(chars: &mut Peekable<Chars>) -> Option<char> {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+    chars.peek().copied()
+}
+
+/// Renders the expression with only the parentheses needed
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (l, op, r) = match self {
+            Expr::Old => return write!(f, "old"),
+            Expr::Num(n) => return write!(f, "{n}"),
+            Expr::Add(l, r) => (l, '+', r),
+            Expr::Sub(l, r) => (l, '-', r),
+            Expr::Mul(l, r) => (l, '*', r),
+        };
+
+        // Operators are left associative so the right hand side needs parentheses
+        // if it binds no tighter
+        let prec = self.precedence();
+
+        if l.precedence() < prec {
+            write!(f, "({l})")?;
+        } else {
+            write!(f, "{l}")?;
+        }
+
+        write!(f, " {op} ")?;
+
+        if r.precedence() <= prec {
+            write!(f, "({r})")
+        } else {
+            write!(f, "{r}")
+        }
+    }
+}
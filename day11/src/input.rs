@@ -0,0 +1,62 @@
+use crate::expr::Expr;
+
+pub enum InputEnt {
+    Monkey(usize),
+    StartItems(Vec<usize>),
+    Operation(Expr),
+    TestDiv(usize),
+    Throw(bool, usize),
+    None,
+}
+
+pub fn input_transform(line: String) -> InputEnt {
+    let mut terms = line.split_whitespace();
+
+    match terms.next() {
+        None => InputEnt::None,
+        Some("Monkey") => InputEnt::Monkey(
+            terms
+                .next()
+                .unwrap()
+                .trim_end_matches(':')
+                .parse::<usize>()
+                .unwrap(),
+        ),
+        Some("Starting") => InputEnt::StartItems(
+            terms
+                .skip(1)
+                .map(|t| t.trim_end_matches(',').parse().unwrap())
+                .collect(),
+        ),
+        Some("Operation:") => {
+            assert_eq!(terms.next(), Some("new"));
+            assert_eq!(terms.next(), Some("="));
+
+            let expr = line.split_once('=').unwrap().1;
+
+            InputEnt::Operation(
+                Expr::parse(expr).unwrap_or_else(|e| panic!("Invalid operation '{expr}': {e}")),
+            )
+        }
+        Some("Test:") => {
+            assert_eq!(terms.next(), Some("divisible"));
+            assert_eq!(terms.next(), Some("by"));
+
+            InputEnt::TestDiv(terms.next().unwrap().parse::<usize>().unwrap())
+        }
+        Some("If") => {
+            let if_bool = match terms.next() {
+                Some("true:") => true,
+                Some("false:") => false,
+                _ => panic!("Unknown if condition"),
+            };
+
+            assert_eq!(terms.next(), Some("throw"));
+            assert_eq!(terms.next(), Some("to"));
+            assert_eq!(terms.next(), Some("monkey"));
+
+            InputEnt::Throw(if_bool, terms.next().unwrap().parse::<usize>().unwrap())
+        }
+        _ => panic!("Unexpected term"),
+    }
+}
@@ -0,0 +1,5 @@
+pub mod expr;
+pub mod input;
+pub mod monkey;
+pub mod sim;
+pub mod worry;
@@ -1,103 +1,53 @@
 use std::error::Error;
+use std::rc::Rc;
 
 use aoc::input::parse_input_vec;
 
-use part1::part1;
-use part2::part2;
-
-mod part1;
-mod part2;
+use day11lib::input::input_transform;
+use day11lib::monkey::{get_input, Monkey};
+use day11lib::sim::{monkey_business, Simulator};
+use day11lib::worry::Worry;
 
 fn main() -> Result<(), Box<dyn Error>> {
     // Get input
     let input = parse_input_vec(11, input_transform)?;
+    let monkeys = get_input(&input);
 
     // Run parts
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
+    println!("Part 1: {}", part1(&monkeys)?);
+    println!("Part 2: {}", part2(&monkeys)?);
 
     Ok(())
 }
 
-#[derive(Debug, Default, Clone)]
-pub enum Operation {
-    #[default]
-    AddOld,
-    MulOld,
-    AddNum(usize),
-    MulNum(usize),
+fn part1(monkeys: &[Monkey]) -> Result<usize, String> {
+    // Run 20 rounds, decreasing worry to 1/3 after each inspection
+    let sim = Simulator::new(monkeys, |item| item, |worry| worry / 3);
+
+    Ok(monkey_business(&sim.inspections(20)?))
 }
 
-// Input parsing
+fn part2(monkeys: &[Monkey]) -> Result<usize, String> {
+    // Run 10,000 rounds with no relief, tracking worry as remainders
+    let sim = Simulator::new(monkeys, worry_builder(monkeys), |worry| worry);
 
-pub enum InputEnt {
-    Monkey(usize),
-    StartItems(Vec<usize>),
-    Operation(Operation),
-    TestDiv(usize),
-    Throw(bool, usize),
-    None,
+    Ok(monkey_business(&sim.inspections(10_000)?))
 }
 
-fn input_transform(line: String) -> InputEnt {
-    let mut terms = line.split_whitespace();
-
-    match terms.next() {
-        None => InputEnt::None,
-        Some("Monkey") => InputEnt::Monkey(
-            terms
-                .next()
-                .unwrap()
-                .trim_end_matches(':')
-                .parse::<usize>()
-                .unwrap(),
-        ),
-        Some("Starting") => InputEnt::StartItems(
-            terms
-                .skip(1)
-                .map(|t| t.trim_end_matches(',').parse().unwrap())
-                .collect(),
-        ),
-        Some("Operation:") => {
-            assert_eq!(terms.next(), Some("new"));
-            assert_eq!(terms.next(), Some("="));
-            assert_eq!(terms.next(), Some("old"));
-
-            InputEnt::Operation(match (terms.next(), terms.next()) {
-                (Some("*"), Some("old")) => Operation::MulOld,
-                (Some("+"), Some("old")) => Operation::AddOld,
-                (Some("*"), Some(num)) => Operation::MulNum(num.parse::<usize>().unwrap()),
-                (Some("+"), Some(num)) => Operation::AddNum(num.parse::<usize>().unwrap()),
-                _ => panic!("Unknown operator"),
-            })
-        }
-        Some("Test:") => {
-            assert_eq!(terms.next(), Some("divisible"));
-            assert_eq!(terms.next(), Some("by"));
+/// Returns a function converting an item to a worry level tracked as remainders
+/// of each monkey's divisor
+fn worry_builder(monkeys: &[Monkey]) -> impl Fn(usize) -> Worry {
+    let divisors = Rc::new(monkeys.iter().map(|m| m.test_div).collect::<Vec<_>>());
 
-            InputEnt::TestDiv(terms.next().unwrap().parse::<usize>().unwrap())
-        }
-        Some("If") => {
-            let if_bool = match terms.next() {
-                Some("true:") => true,
-                Some("false:") => false,
-                _ => panic!("Unknown if condition"),
-            };
-
-            assert_eq!(terms.next(), Some("throw"));
-            assert_eq!(terms.next(), Some("to"));
-            assert_eq!(terms.next(), Some("monkey"));
-
-            InputEnt::Throw(if_bool, terms.next().unwrap().parse::<usize>().unwrap())
-        }
-        _ => panic!("Unexpected term"),
-    }
+    move |item| Worry::new(item, divisors.clone())
 }
 
 #[cfg(test)]
 mod tests {
     use aoc::input::parse_test_vec;
 
+    use day11lib::expr::{Arith, Expr};
+
     use super::*;
 
     const EXAMPLE1: &str = "Monkey 0:
@@ -132,7 +82,107 @@ Test: divisible by 17
     #[test]
     fn test1() {
         let input = parse_test_vec(EXAMPLE1, input_transform).unwrap();
-        assert_eq!(part1(&input), 10605);
-        assert_eq!(part2(&input), 2713310158);
+        let monkeys = get_input(&input);
+        assert_eq!(part1(&monkeys).unwrap(), 10605);
+        assert_eq!(part2(&monkeys).unwrap(), 2713310158);
+    }
+
+    #[test]
+    fn test_simulation() {
+        let input = parse_test_vec(EXAMPLE1, input_transform).unwrap();
+        let monkeys = get_input(&input);
+
+        // With relief
+        let sim = Simulator::new(&monkeys, |item| item, |worry| worry / 3);
+        let report = sim.run(20).unwrap();
+
+        assert_eq!(report.inspections(), [101, 95, 7, 105]);
+        assert_eq!(report.inspections(), sim.inspections(20).unwrap());
+
+        // Item 79 is inspected by monkey 0, thrown to monkey 3 as 500, inspected
+        // and thrown to monkey 1 as 167
+        assert_eq!(report.journeys[0][0], [0, 3]);
+        assert_eq!(report.journeys[0][1], [1, 2, 3]);
+
+        // Without relief
+        let sim = Simulator::new(&monkeys, worry_builder(&monkeys), |worry| worry);
+        let report = sim.run(10_000).unwrap();
+
+        assert_eq!(report.round_inspections[0], [2, 4, 3, 6]);
+        assert_eq!(report.inspections(), [52166, 47830, 1938, 52013]);
+
+        // Extrapolated cycles match the full simulation
+        for rounds in [0, 1, 20, 1000, 9999, 10_000] {
+            let mut expected = vec![0; monkeys.len()];
+
+            for round in &report.round_inspections[..rounds] {
+                expected.iter_mut().zip(round).for_each(|(e, c)| *e += c);
+            }
+
+            assert_eq!(sim.inspections(rounds).unwrap(), expected);
+        }
+
+        // Worry levels going out of range are reported
+        let input =
+            parse_test_vec(&EXAMPLE1.replace("old + 3", "old - 100"), input_transform).unwrap();
+        let monkeys = get_input(&input);
+        let sim = Simulator::new(&monkeys, |item| item, |worry| worry / 3);
+
+        assert_eq!(
+            sim.inspections(20).unwrap_err(),
+            "Monkey 3: 'old - 100' is out of range"
+        );
+        assert!(sim.run(20).is_err());
+    }
+
+    #[test]
+    fn test_expr() {
+        let expr = Expr::parse("(old - 2) * (old + 3) * 2 - old").unwrap();
+        assert_eq!(expr.to_string(), "(old - 2) * (old + 3) * 2 - old");
+        assert_eq!(expr.eval(&10), Ok(198));
+
+        let expr = Expr::parse("7 - (old - 1)*old").unwrap();
+        assert_eq!(expr.to_string(), "7 - (old - 1) * old");
+        assert_eq!(expr.eval(&2), Ok(5));
+
+        // Evaluate on residues, including going negative
+        let divisors = Rc::new(vec![3, 5, 7]);
+        let worry = expr.eval(&Worry::new(4, divisors)).unwrap();
+        assert_eq!(
+            [3, 5, 7].map(|d| (-5_isize).rem_euclid(d) == 0),
+            [3, 5, 7].map(|d| worry.divisible(d))
+        );
+
+        // Residues of large divisors, where the products go past usize
+        let divisors = Rc::new(vec![(1 << 40) + 15, usize::MAX]);
+        let expr = Expr::parse("old * old - 4").unwrap();
+        let worry = expr.eval(&Worry::new(usize::MAX - 2, divisors)).unwrap();
+        assert!(!worry.divisible((1 << 40) + 15));
+        assert!(worry.divisible(usize::MAX));
+
+        // Underflow and overflow
+        let expr = Expr::parse("old - 100").unwrap();
+        assert_eq!(
+            expr.eval(&5),
+            Err("'old - 100' is out of range".to_string())
+        );
+
+        let expr = Expr::parse("old * old * old + 1").unwrap();
+        assert_eq!(expr.eval(&1000), Ok(1_000_000_001));
+        assert_eq!(
+            expr.eval(&(1 << 22)),
+            Err("'old * old * old' is out of range".to_string())
+        );
+
+        assert_eq!(
+            Expr::parse("old +").unwrap_err(),
+            "unexpected end of expression"
+        );
+        assert_eq!(Expr::parse("(old * 2").unwrap_err(), "expected ')'");
+        assert_eq!(Expr::parse("old / 2").unwrap_err(), "unexpected '/'");
+        assert_eq!(
+            Expr::parse("new + 1").unwrap_err(),
+            "unknown variable 'new'"
+        );
     }
 }
@@ -0,0 +1,51 @@
+use crate::expr::Expr;
+use crate::input::InputEnt;
+
+#[derive(Debug, Default, Clone)]
+pub struct Monkey {
+    pub monkey: usize,
+    pub items: Vec<usize>,
+    pub operation: Expr,
+    pub test_div: usize,
+    pub true_throw: usize,
+    pub false_throw: usize,
+}
+
+pub fn get_input(input: &[InputEnt]) -> Vec<Monkey> {
+    let mut monkeys = Vec::new();
+    let mut monkey = Monkey::default();
+    let mut updated = false;
+
+    for ent in input {
+        let mut update = true;
+
+        match ent {
+            InputEnt::Monkey(n) => monkey.monkey = *n,
+            InputEnt::StartItems(items) => monkey.items = items.clone(),
+            InputEnt::Operation(op) => monkey.operation = op.clone(),
+            InputEnt::TestDiv(n) => monkey.test_div = *n,
+            InputEnt::Throw(cond, n) => {
+                if *cond {
+                    monkey.true_throw = *n
+                } else {
+                    monkey.false_throw = *n
+                }
+            }
+            InputEnt::None => {
+                if updated {
+                    monkeys.push(monkey);
+                }
+                monkey = Monkey::default();
+                update = false;
+            }
+        }
+
+        updated = update;
+    }
+
+    if updated {
+        monkeys.push(monkey);
+    }
+
+    monkeys
+}
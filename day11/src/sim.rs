@@ -0,0 +1,176 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::expr::Arith;
+use crate::monkey::Monkey;
+
+/// Simulates monkeys throwing items between them. An item's journey only depends
+/// on its worry level and the monkey holding it, so each item is simulated on its own
+pub struct Simulator<'a, T, R> {
+    monkeys: &'a [Monkey],
+    items: Vec<(usize, T)>,
+    relief: R,
+}
+
+/// Record of every inspection in a simulation
+pub struct Report {
+    /// Inspections by each monkey in each round
+    pub round_inspections: Vec<Vec<usize>>,
+    /// Monkeys inspecting each item in each round. Items are numbered in order of
+    /// the monkeys holding them at the start
+    pub journeys: Vec<Vec<Vec<usize>>>,
+}
+
+impl<'a, T, R> Simulator<'a, T, R>
+where
+    T: Arith + Eq + Hash,
+    R: Fn(T) -> T,
+{
+    /// Creates a new simulator, converting the starting items to worry levels.
+    /// The relief function is applied to the worry level after each inspection
+    pub fn new(monkeys: &'a [Monkey], worry: impl Fn(usize) -> T, relief: R) -> Self {
+        let items = monkeys
+            .iter()
+            .enumerate()
+            .flat_map(|(m, monkey)| monkey.items.iter().map(move |item| (m, *item)))
+            .map(|(m, item)| (m, worry(item)))
+            .collect();
+
+        Self {
+            monkeys,
+            items,
+            relief,
+        }
+    }
+
+    /// Simulates a number of rounds, recording every inspection. Fails if a worry
+    /// level goes out of range
+    pub fn run(&self, rounds: usize) -> Result<Report, String> {
+        let mut round_inspections = vec![vec![0; self.monkeys.len()]; rounds];
+        let mut journeys = Vec::with_capacity(self.items.len());
+
+        for (monkey, worry) in &self.items {
+            let mut state = (*monkey, worry.clone());
+            let mut journey = Vec::with_capacity(rounds);
+
+            for inspections in round_inspections.iter_mut() {
+                let mut visits = Vec::new();
+
+                state = self.item_round(state, |m| visits.push(m))?;
+
+                visits.iter().for_each(|m| inspections[*m] += 1);
+                journey.push(visits);
+            }
+
+            journeys.push(journey);
+        }
+
+        Ok(Report {
+            round_inspections,
+            journeys,
+        })
+    }
+
+    /// Counts the inspections by each monkey over a number of rounds. Once an item
+    /// returns to a monkey with a worry level it has had before its journey repeats,
+    /// so the rest of the rounds are extrapolated from the cycle. Fails if a worry
+    /// level goes out of range
+    pub fn inspections(&self, rounds: usize) -> Result<Vec<usize>, String> {
+        let mut inspections = vec![0; self.monkeys.len()];
+
+        for (monkey, worry) in &self.items {
+            let mut seen = HashMap::new();
+            let mut history = Vec::new();
+            let mut state = (*monkey, worry.clone());
+
+            while history.len() < rounds {
+                if let Some(start) = seen.insert(state.clone(), history.len()) {
+                    // Rounds from the start of the cycle repeat until the end
+                    let remaining = rounds - history.len();
+                    let cycle: &[Vec<usize>] = &history[start..];
+                    let (cycles, extra) = (remaining / cycle.len(), remaining % cycle.len());
+
+                    for (i, visits) in cycle.iter().enumerate() {
+                        let count = if i < extra { cycles + 1 } else { cycles };
+
+                        visits.iter().for_each(|m| inspections[*m] += count);
+                    }
+
+                    break;
+                }
+
+                let mut visits = Vec::new();
+
+                state = self.item_round(state, |m| visits.push(m))?;
+                history.push(visits);
+            }
+
+            // Add the simulated rounds
+            history.iter().flatten().for_each(|m| inspections[*m] += 1);
+        }
+
+        Ok(inspections)
+    }
+
+    /// Plays one round for an item, calling visit for each monkey which inspects it.
+    /// Returns the monkey holding the item at the end of the round and its worry level
+    fn item_round(
+        &self,
+        state: (usize, T),
+        mut visit: impl FnMut(usize),
+    ) -> Result<(usize, T), String> {
+        let (mut monkey, mut worry) = state;
+
+        loop {
+            let m = &self.monkeys[monkey];
+
+            visit(monkey);
+
+            // Apply operation to the worry and then relief
+            worry = (self.relief)(
+                m.operation
+                    .eval(&worry)
+                    .map_err(|e| format!("Monkey {monkey}: {e}"))?,
+            );
+
+            // Work out which monkey to move the item to
+            let target = if worry.divisible(m.test_div) {
+                m.true_throw
+            } else {
+                m.false_throw
+            };
+
+            // Monkeys take turns in order, so an item thrown back waits for the next round
+            if target <= monkey {
+                break Ok((target, worry));
+            }
+
+            monkey = target;
+        }
+    }
+}
+
+impl Report {
+    /// Returns the total inspections by each monkey
+    pub fn inspections(&self) -> Vec<usize> {
+        let mut totals = vec![0; self.round_inspections.first().map_or(0, |r| r.len())];
+
+        for round in &self.round_inspections {
+            for (total, count) in totals.iter_mut().zip(round) {
+                *total += count;
+            }
+        }
+
+        totals
+    }
+}
+
+/// Returns the product of the two highest inspection counts
+pub fn monkey_business(inspections: &[usize]) -> usize {
+    let mut inspections = inspections.to_vec();
+
+    inspections.sort_by_key(|w| Reverse(*w));
+
+    inspections.iter().take(2).product()
+}
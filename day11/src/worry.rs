@@ -0,0 +1,66 @@
+use std::rc::Rc;
+
+use crate::expr::Arith;
+
+/// Worry level stored as remainders for each monkey's divisor
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Worry {
+    remainders: Vec<usize>,
+    divisors: Rc<Vec<usize>>,
+}
+
+impl Worry {
+    pub fn new(value: usize, divisors: Rc<Vec<usize>>) -> Self {
+        let remainders = divisors.iter().map(|d| value % *d).collect();
+
+        Self {
+            remainders,
+            divisors,
+        }
+    }
+
+    /// Combines remainders with another worry level. The operation works in u128 so
+    /// it can't overflow for any divisor, and the result is reduced back below it
+    fn combine(&self, rhs: &Self, op: impl Fn(u128, u128, u128) -> u128) -> Self {
+        let remainders = self
+            .remainders
+            .iter()
+            .zip(rhs.remainders.iter())
+            .zip(self.divisors.iter())
+            .map(|((l, r), d)| op(*l as u128, *r as u128, *d as u128) as usize)
+            .collect();
+
+        Self {
+            remainders,
+            divisors: self.divisors.clone(),
+        }
+    }
+}
+
+impl Arith for Worry {
+    fn constant(&self, value: usize) -> Self {
+        Worry::new(value, self.divisors.clone())
+    }
+
+    fn add(&self, rhs: &Self) -> Option<Self> {
+        Some(self.combine(rhs, |l, r, d| (l + r) % d))
+    }
+
+    fn sub(&self, rhs: &Self) -> Option<Self> {
+        Some(self.combine(rhs, |l, r, d| (l + d - r) % d))
+    }
+
+    fn mul(&self, rhs: &Self) -> Option<Self> {
+        Some(self.combine(rhs, |l, r, d| (l * r) % d))
+    }
+
+    fn divisible(&self, divisor: usize) -> bool {
+        let pos = self
+            .divisors
+            .iter()
+            .position(|d| *d == divisor)
+            .expect("Unknown divisor");
+
+        self.remainders[pos] == 0
+    }
+}
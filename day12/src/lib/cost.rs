@@ -0,0 +1,41 @@
+/// Cost model for steps between heights
+#[derive(Debug, Clone)]
+pub struct CostModel {
+    /// Maximum height which can be climbed in one step
+    pub max_climb: u8,
+    /// Maximum height which can be descended in one step
+    pub max_descent: u8,
+    /// Cost of every step
+    pub step_cost: usize,
+    /// Extra cost for each unit of height climbed
+    pub climb_cost: usize,
+    /// Extra cost for each unit of height descended
+    pub descent_cost: usize,
+}
+
+impl CostModel {
+    /// Every step costs 1, climbing at most max_climb and descending any amount
+    pub fn unit(max_climb: u8) -> Self {
+        Self {
+            max_climb,
+            max_descent: u8::MAX,
+            step_cost: 1,
+            climb_cost: 0,
+            descent_cost: 0,
+        }
+    }
+
+    /// Returns the cost of a step between two heights, or None if the step is not allowed
+    pub fn cost(&self, from: u8, to: u8) -> Option<usize> {
+        if to >= from {
+            let climb = to - from;
+
+            (climb <= self.max_climb).then_some(self.step_cost + (climb as usize * self.climb_cost))
+        } else {
+            let descent = from - to;
+
+            (descent <= self.max_descent)
+                .then_some(self.step_cost + (descent as usize * self.descent_cost))
+        }
+    }
+}
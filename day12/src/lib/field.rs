@@ -0,0 +1,73 @@
+use std::collections::VecDeque;
+
+use super::pos::{pred_path, Pos};
+use super::Map;
+
+/// Distance to every reachable position from the nearest of a set of sources
+pub struct DistanceField {
+    dist: Vec<Vec<Option<usize>>>,
+    pred: Vec<Vec<Option<Pos>>>,
+}
+
+impl DistanceField {
+    /// Builds the distance field with a breadth first search from all sources at once
+    pub(super) fn build<G>(map: &Map, sources: &[Pos], neigh_chk: G) -> Self
+    where
+        G: Fn(u8, u8) -> bool,
+    {
+        let width = map.max_x as usize + 1;
+        let height = map.max_y as usize + 1;
+
+        let mut field = Self {
+            dist: vec![vec![None; width]; height],
+            pred: vec![vec![None; width]; height],
+        };
+
+        // Add the sources to the work queue
+        let mut work_queue = VecDeque::new();
+
+        for source in sources {
+            if field.distance(source).is_none() {
+                field.dist[source.y as usize][source.x as usize] = Some(0);
+                work_queue.push_back(source.clone());
+            }
+        }
+
+        // Get next position
+        while let Some(pos) = work_queue.pop_front() {
+            let dist = field.distance(&pos).unwrap();
+            let from_height = map.height(&pos);
+
+            for n in map.adjacent(&pos) {
+                if field.distance(&n).is_none() && neigh_chk(from_height, map.height(&n)) {
+                    field.dist[n.y as usize][n.x as usize] = Some(dist + 1);
+                    field.pred[n.y as usize][n.x as usize] = Some(pos.clone());
+                    work_queue.push_back(n);
+                }
+            }
+        }
+
+        field
+    }
+
+    /// Returns the distance to a position, or None if it can't be reached
+    pub fn distance(&self, pos: &Pos) -> Option<usize> {
+        self.dist[pos.y as usize][pos.x as usize]
+    }
+
+    /// Returns the shortest path from the nearest source to a position, or None if it can't be reached
+    pub fn path(&self, to: &Pos) -> Option<Vec<Pos>> {
+        self.distance(to)?;
+
+        Some(pred_path(&self.pred, to))
+    }
+
+    /// Returns all reachable positions with their distances
+    pub fn reachable(&self) -> impl Iterator<Item = (Pos, usize)> + '_ {
+        self.dist.iter().enumerate().flat_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .filter_map(move |(x, d)| d.map(|d| (Pos::new(x as u16, y as u16), d)))
+        })
+    }
+}
@@ -5,12 +5,22 @@ use std::{
 
 use aoc::gif::Gif;
 
-use self::palette::COLOUR_MAP;
-use self::pos::Pos;
+use self::palette::{COLOUR_MAP, TERRAIN_PALETTE};
+use self::pos::pred_path;
+use self::terrain::TerrainView;
 use self::workitem::WorkItem;
 
+pub use self::cost::CostModel;
+pub use self::field::DistanceField;
+pub use self::pos::Pos;
+pub use self::search::{Algorithm, Route};
+
+mod cost;
+mod field;
 mod palette;
 mod pos;
+mod search;
+mod terrain;
 mod workitem;
 
 /// Map
@@ -77,8 +87,43 @@ impl Map {
         self.heights[pos.y as usize][pos.x as usize]
     }
 
-    /// Calculate the shortest path from a position to a position matching a criteria
-    pub fn shortest_path<F, G>(&self, start: &Pos, end_chk: F, neigh_chk: G) -> usize
+    /// Returns all positions with a given height
+    pub fn positions_at_height(&self, height: u8) -> Vec<Pos> {
+        self.heights
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter(|(_, h)| **h == height)
+                    .map(move |(x, _)| Pos::new(x as u16, y as u16))
+            })
+            .collect()
+    }
+
+    /// Calculate the distance from the nearest of a set of sources to every reachable position
+    pub fn distance_field<G>(&self, sources: &[Pos], neigh_chk: G) -> DistanceField
+    where
+        G: Fn(u8, u8) -> bool,
+    {
+        DistanceField::build(self, sources, neigh_chk)
+    }
+
+    /// Calculate the cheapest route between two positions with a weighted cost model.
+    /// Returns None if the end can't be reached
+    pub fn cheapest_path(
+        &self,
+        start: &Pos,
+        end: &Pos,
+        costs: &CostModel,
+        algorithm: Algorithm,
+    ) -> Option<Route> {
+        search::cheapest_path(self, start, end, costs, algorithm)
+    }
+
+    /// Calculate the shortest path from a position to a position matching a criteria.
+    /// Returns None if no matching position can be reached
+    pub fn shortest_path<F, G>(&self, start: &Pos, end_chk: F, neigh_chk: G) -> Option<usize>
     where
         F: Fn(&Pos) -> bool,
         G: Fn(u8, u8) -> bool,
@@ -105,7 +150,6 @@ impl Map {
             },
         )
         .expect("Unexpected error")
-        .expect("Shortest path not found")
     }
 
     /// Generate animated GIF of the shortest path algorithm
@@ -147,41 +191,55 @@ impl Map {
             frame[y as usize][x as usize] = (frame[y as usize][x as usize] % 26) + (col * 26);
         };
 
-        let mut last_len = 0;
+        // Frame with visited positions and the work queue coloured, updated as the search progresses
+        let mut frame = map_frame.clone();
+
+        // Predecessor of each position dequeued so far
+        let mut preds = vec![vec![None; self.max_x as usize + 1]; self.max_y as usize + 1];
+
+        let mut enqueued = 0;
+        let mut dequeued = 0;
+        let mut last_len = None;
 
-        // Callback to receive state
-        let state_cb = |cur_pos: &WorkItem<Vec<Pos>>,
-                        work_queue: &VecDeque<WorkItem<Vec<Pos>>>,
-                        visited: &HashSet<Pos>,
+        // Callback to receive state. Work items hold the path length and predecessor
+        let state_cb = |cur_pos: &WorkItem<(usize, Option<Pos>)>,
+                        work_queue: &VecDeque<WorkItem<(usize, Option<Pos>)>>,
+                        _: &HashSet<Pos>,
                         result: &mut Option<Vec<Pos>>|
          -> Result<bool, Box<dyn Error>> {
+            let (len, pred) = &cur_pos.data;
+
+            preds[cur_pos.pos.y as usize][cur_pos.pos.x as usize] = pred.clone();
+
+            // Colour positions added to the work queue since the last call red
+            dequeued += 1;
+
+            let added = (dequeued + work_queue.len()) - enqueued;
+            enqueued += added;
+
+            for v in work_queue.iter().rev().take(added) {
+                colour_pixel(&mut frame, v.pos.x, v.pos.y, 1);
+            }
+
+            // Colour the current position visited blue
+            colour_pixel(&mut frame, cur_pos.pos.x, cur_pos.pos.y, 2);
+
             // Finished?
             if result.is_none() && end_chk(&cur_pos.pos) {
                 // Save first result
-                *result = Some(cur_pos.data.clone());
+                *result = Some(pred_path(&preds, &cur_pos.pos));
             }
 
             // Only plot if path length has increased
-            if cur_pos.data.len() != last_len {
-                last_len = cur_pos.data.len();
-
-                // Clone the map frame
-                let mut next_frame = map_frame.clone();
-
-                // Colour visited blue
-                for v in visited {
-                    colour_pixel(&mut next_frame, v.x, v.y, 2);
-                }
+            if last_len != Some(*len) {
+                last_len = Some(*len);
 
-                // Colour work queue red
-                for v in work_queue {
-                    colour_pixel(&mut next_frame, v.pos.x, v.pos.y, 1);
-                }
+                let mut next_frame = frame.clone();
 
                 // Colour current path / final path yellow
                 let plot_path = match result {
                     Some(p) => p,
-                    None => &cur_pos.data,
+                    None => &pred_path(&preds, &cur_pos.pos),
                 };
 
                 for p in plot_path.iter() {
@@ -200,22 +258,16 @@ impl Map {
             .shortest_path_internal(
                 start,
                 neigh_chk,
-                |n, w| {
-                    let mut path = match w {
-                        Some(w) => w.data.clone(),
-                        None => Vec::new(),
-                    };
-
-                    path.push(n.clone());
-
-                    WorkItem::new(n, path)
+                |n, w| match w {
+                    Some(w) => WorkItem::new(n, (w.data.0 + 1, Some(w.pos.clone()))),
+                    None => WorkItem::new(n, (0, None)),
                 },
                 state_cb,
             )?
             .expect("No path found");
 
         // Draw map frame with final path
-        let mut next_frame = map_frame.clone();
+        let mut next_frame = map_frame;
 
         for p in path {
             colour_pixel(&mut next_frame, p.x, p.y, 3)
@@ -238,14 +290,55 @@ impl Map {
         Ok(())
     }
 
+    /// Generate an isometric view of the terrain with a path drawn over it, and optionally
+    /// an animation of the terrain rotating
+    pub fn terrain_vis(
+        &self,
+        image: &str,
+        anim: Option<&str>,
+        path: &[Pos],
+        tile: u16,
+        angle: f64,
+    ) -> Result<(), Box<dyn Error>> {
+        const ROTATE_STEP: usize = 5;
+        const ROTATE_DELAY: u16 = 8;
+
+        let view = TerrainView::new(self, tile);
+
+        let new_gif = |file| {
+            Gif::new(
+                file,
+                &TERRAIN_PALETTE,
+                view.width() as u16,
+                view.height() as u16,
+                1,
+                1,
+            )
+        };
+
+        // Draw the static image
+        new_gif(image)?.draw_frame(view.render(path, angle), 0)?;
+
+        // Draw the rotating animation
+        if let Some(anim) = anim {
+            let mut gif = new_gif(anim)?;
+
+            for step in (0..360).step_by(ROTATE_STEP) {
+                gif.draw_frame(view.render(path, angle + step as f64), ROTATE_DELAY)?;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Calculate the shortest path from a position to a position matching a criteria with state callback
-    fn shortest_path_internal<G, H, I, T>(
+    fn shortest_path_internal<G, H, I, T, R>(
         &self,
         start: &Pos,
         neigh_chk: G,
         workitem: H,
         mut state_cb: I,
-    ) -> Result<Option<T>, Box<dyn Error>>
+    ) -> Result<Option<R>, Box<dyn Error>>
     where
         G: Fn(u8, u8) -> bool,
         H: Fn(Pos, Option<&WorkItem<T>>) -> WorkItem<T>,
@@ -253,11 +346,10 @@ impl Map {
             &WorkItem<T>,
             &VecDeque<WorkItem<T>>,
             &HashSet<Pos>,
-            &mut Option<T>,
+            &mut Option<R>,
         ) -> Result<bool, Box<dyn Error>>,
-        T: Default,
     {
-        let mut result: Option<T> = None;
+        let mut result: Option<R> = None;
 
         // Visited positions hash set
         let mut visited = HashSet::new();
@@ -292,17 +384,19 @@ impl Map {
     where
         F: Fn(u8, u8) -> bool,
     {
-        let mut neigh = Vec::with_capacity(4);
         let from_height = self.height(from_pos);
 
-        let mut add = |x, y| {
-            let to_pos = Pos::new(x, y);
-            let to_height = self.height(&to_pos);
+        self.adjacent(from_pos)
+            .into_iter()
+            .filter(|to_pos| chk(from_height, self.height(to_pos)) && !visited.contains(to_pos))
+            .collect()
+    }
 
-            if chk(from_height, to_height) && !visited.contains(&to_pos) {
-                neigh.push(to_pos);
-            }
-        };
+    /// Returns a vector of positions adjacent to a position
+    fn adjacent(&self, from_pos: &Pos) -> Vec<Pos> {
+        let mut adj = Vec::with_capacity(4);
+
+        let mut add = |x, y| adj.push(Pos::new(x, y));
 
         // Left
         if from_pos.x > 0 {
@@ -324,6 +418,6 @@ impl Map {
             add(from_pos.x, from_pos.y + 1);
         }
 
-        neigh
+        adj
     }
 }
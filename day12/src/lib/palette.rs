@@ -1,6 +1,14 @@
 use lab::Lab;
 use lazy_static::lazy_static;
 
+pub const HEIGHTS: u8 = 26;
+pub const SHADES: u8 = 8;
+
+pub const BG_COLOUR: u8 = 0;
+pub const TERRAIN_BASE: u8 = 1; // Shades for each height
+pub const PATH_BASE: u8 = TERRAIN_BASE + (HEIGHTS * SHADES); // Path shades
+pub const LINE_COLOUR: u8 = PATH_BASE + SHADES;
+
 const COLOUR_STEP: u8 = 6;
 const COLOUR_MAX: u8 = 255;
 const MIN_COLOUR_COMPONENT: u8 = COLOUR_MAX - (26 * COLOUR_STEP);
@@ -43,4 +51,32 @@ lazy_static! {
         })
         .collect()
     };
+
+    /// Terrain colour palette
+    pub static ref TERRAIN_PALETTE: Vec<[u8; 3]> = {
+        // Green valleys, brown slopes and snowy peaks
+        let stops = [(0.0, [40.0, 110.0, 40.0]), (0.6, [130.0, 110.0, 60.0]), (1.0, [240.0, 240.0, 240.0])];
+
+        let height_colour = |h: u8| {
+            let t = h as f64 / (HEIGHTS - 1) as f64;
+            let i = if t <= stops[1].0 { 0 } else { 1 };
+            let ((t0, c0), (t1, c1)) = (stops[i], stops[i + 1]);
+            let f = (t - t0) / (t1 - t0);
+
+            [0, 1, 2].map(|c| c0[c] + ((c1[c] - c0[c]) * f))
+        };
+
+        let shaded = |rgb: [f64; 3], shade: u8| {
+            let f = 0.3 + ((0.7 * shade as f64) / (SHADES - 1) as f64);
+
+            rgb.map(|c| (c * f) as u8)
+        };
+
+        [[0x00, 0x00, 0x00]] // Background
+            .into_iter()
+            .chain((0..HEIGHTS).flat_map(|h| (0..SHADES).map(move |s| shaded(height_colour(h), s))))
+            .chain((0..SHADES).map(|s| shaded([255.0, 200.0, 0.0], s))) // Path
+            .chain([[0xff, 0xff, 0x60]]) // Path line
+            .collect()
+    };
 }
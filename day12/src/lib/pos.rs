@@ -1,5 +1,5 @@
 /// Board position
-#[derive(Debug, Hash, PartialEq, Eq, Clone)]
+#[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Clone)]
 pub struct Pos {
     pub x: u16,
     pub y: u16,
@@ -10,3 +10,18 @@ impl Pos {
         Self { x, y }
     }
 }
+
+/// Builds the path to a position by following predecessors back to the start
+pub(super) fn pred_path(preds: &[Vec<Option<Pos>>], to: &Pos) -> Vec<Pos> {
+    let mut path = vec![to.clone()];
+    let mut pos = to;
+
+    while let Some(pred) = &preds[pos.y as usize][pos.x as usize] {
+        path.push(pred.clone());
+        pos = pred;
+    }
+
+    path.reverse();
+
+    path
+}
@@ -0,0 +1,94 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use super::cost::CostModel;
+use super::pos::{pred_path, Pos};
+use super::Map;
+
+/// Weighted search algorithm
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    Dijkstra,
+    /// A* with the Manhattan distance to the end as the heuristic
+    AStar,
+}
+
+/// Cheapest route found by a weighted search
+#[derive(Debug, Clone)]
+pub struct Route {
+    /// Total cost of the route
+    pub cost: usize,
+    /// Positions from start to end
+    pub path: Vec<Pos>,
+    /// Number of positions expanded by the search
+    pub expanded: usize,
+}
+
+/// Finds the cheapest route between two positions
+pub(super) fn cheapest_path(
+    map: &Map,
+    start: &Pos,
+    end: &Pos,
+    costs: &CostModel,
+    algorithm: Algorithm,
+) -> Option<Route> {
+    let width = map.max_x as usize + 1;
+    let height = map.max_y as usize + 1;
+
+    // Lower bound of the cost from a position to the end. Every step costs at
+    // least the step cost so this never overestimates
+    let estimate = |pos: &Pos| match algorithm {
+        Algorithm::Dijkstra => 0,
+        Algorithm::AStar => {
+            (pos.x.abs_diff(end.x) as usize + pos.y.abs_diff(end.y) as usize) * costs.step_cost
+        }
+    };
+
+    let mut best = vec![vec![None; width]; height];
+    let mut pred: Vec<Vec<Option<Pos>>> = vec![vec![None; width]; height];
+    let mut done = vec![vec![false; width]; height];
+    let mut expanded = 0;
+
+    // Priority queue ordered by estimated total cost
+    let mut work_queue = BinaryHeap::new();
+
+    best[start.y as usize][start.x as usize] = Some(0);
+    work_queue.push(Reverse((estimate(start), 0, start.clone())));
+
+    while let Some(Reverse((_, cost, pos))) = work_queue.pop() {
+        if done[pos.y as usize][pos.x as usize] {
+            continue;
+        }
+
+        done[pos.y as usize][pos.x as usize] = true;
+        expanded += 1;
+
+        if pos == *end {
+            return Some(Route {
+                cost,
+                path: pred_path(&pred, &pos),
+                expanded,
+            });
+        }
+
+        let from_height = map.height(&pos);
+
+        for n in map.adjacent(&pos) {
+            let step = match costs.cost(from_height, map.height(&n)) {
+                Some(step) => step,
+                None => continue,
+            };
+
+            let (x, y) = (n.x as usize, n.y as usize);
+            let new_cost = cost + step;
+
+            if !done[y][x] && best[y][x].is_none_or(|c| new_cost < c) {
+                best[y][x] = Some(new_cost);
+                pred[y][x] = Some(pos.clone());
+                work_queue.push(Reverse((new_cost + estimate(&n), new_cost, n)));
+            }
+        }
+    }
+
+    None
+}
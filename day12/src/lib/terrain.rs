@@ -0,0 +1,177 @@
+use super::palette::{BG_COLOUR, HEIGHTS, LINE_COLOUR, PATH_BASE, SHADES, TERRAIN_BASE};
+use super::pos::Pos;
+use super::Map;
+
+/// Screen pixels per unit of height
+const HEIGHT_SCALE: f64 = 3.0;
+/// Border around the image in pixels
+const BORDER: f64 = 8.0;
+/// Steepness of the terrain used for shading
+const RELIEF: f64 = 0.5;
+
+/// Isometric view of a map's terrain which can be rotated about the centre
+pub struct TerrainView<'a> {
+    map: &'a Map,
+    tile: f64,
+    width: usize,
+    height: usize,
+    origin: (f64, f64),
+}
+
+impl<'a> TerrainView<'a> {
+    /// Creates a view with a given tile size in pixels, sized to fit the map at any rotation
+    pub fn new(map: &'a Map, tile: u16) -> Self {
+        let tile = tile as f64;
+
+        // Furthest any position can be from the centre of the map
+        let radius = ((map.max_x as f64 + 1.0) / 2.0).hypot((map.max_y as f64 + 1.0) / 2.0) * tile;
+        let peak = (HEIGHTS - 1) as f64 * HEIGHT_SCALE;
+
+        let width = ((radius + tile + BORDER) * 2.0) as usize;
+        let height = (radius + peak + (tile * 2.0) + (BORDER * 2.0)) as usize;
+
+        Self {
+            map,
+            tile,
+            width,
+            height,
+            origin: (width as f64 / 2.0, BORDER + peak + tile + (radius / 2.0)),
+        }
+    }
+
+    /// Returns the image width in pixels
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the image height in pixels
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Draws the terrain rotated by an angle in degrees with a path over it
+    pub fn render(&self, path: &[Pos], angle: f64) -> Vec<Vec<u8>> {
+        let mut frame = vec![vec![BG_COLOUR; self.width]; self.height];
+        let mut depth = vec![vec![f64::NEG_INFINITY; self.width]; self.height];
+
+        let mut on_path =
+            vec![vec![false; self.map.max_x as usize + 1]; self.map.max_y as usize + 1];
+
+        for p in path {
+            on_path[p.y as usize][p.x as usize] = true;
+        }
+
+        let angle = angle.to_radians();
+        let half = self.tile / 2.0;
+
+        // Draw each position as a column with a shaded top
+        for (y, row) in self.map.heights.iter().enumerate() {
+            for (x, h) in row.iter().enumerate() {
+                let (sx, top, d) = self.project(x as f64, y as f64, *h as f64, angle);
+                let (_, base, _) = self.project(x as f64, y as f64, 0.0, angle);
+
+                let shade = self.shade(x, y, angle);
+
+                let colour_base = if on_path[y][x] {
+                    PATH_BASE
+                } else {
+                    TERRAIN_BASE + (h * SHADES)
+                };
+
+                let top_colour = colour_base + shade;
+                let side_colour = colour_base + shade.saturating_sub(3);
+
+                let x_range = (sx - half).floor() as usize..=(sx + half).ceil() as usize;
+
+                for py in
+                    (top - (half / 2.0)).round() as usize..=(base + (half / 2.0)).round() as usize
+                {
+                    let colour = if (py as f64) <= top + (half / 2.0) {
+                        top_colour
+                    } else {
+                        side_colour
+                    };
+
+                    for px in x_range.clone() {
+                        if d > depth[py][px] {
+                            depth[py][px] = d;
+                            frame[py][px] = colour;
+                        }
+                    }
+                }
+            }
+        }
+
+        // Draw the path as a line joining the tops of the columns, hidden behind nearer terrain
+        for step in path.windows(2) {
+            let (x0, y0, d0) = self.project_pos(&step[0], angle);
+            let (x1, y1, d1) = self.project_pos(&step[1], angle);
+
+            let steps = (x1 - x0).abs().max((y1 - y0).abs()).ceil().max(1.0) as usize;
+
+            for i in 0..=steps {
+                let t = i as f64 / steps as f64;
+                let (px, py) = (
+                    (x0 + ((x1 - x0) * t)).round() as usize,
+                    (y0 + ((y1 - y0) * t)).round() as usize,
+                );
+                let d = d0 + ((d1 - d0) * t);
+
+                for (px, py) in [(px, py), (px + 1, py), (px, py + 1), (px + 1, py + 1)] {
+                    if d + 0.5 >= depth[py][px] {
+                        frame[py][px] = LINE_COLOUR;
+                    }
+                }
+            }
+        }
+
+        frame
+    }
+
+    /// Projects a map position and height to screen coordinates and depth (larger is nearer)
+    fn project(&self, x: f64, y: f64, h: f64, angle: f64) -> (f64, f64, f64) {
+        // Rotate about the centre of the map
+        let cx = x - (self.map.max_x as f64 / 2.0);
+        let cy = y - (self.map.max_y as f64 / 2.0);
+
+        let rx = (cx * angle.cos()) - (cy * angle.sin());
+        let ry = (cx * angle.sin()) + (cy * angle.cos());
+
+        (
+            self.origin.0 + (rx * self.tile),
+            self.origin.1 + (ry * self.tile / 2.0) - (h * HEIGHT_SCALE),
+            ry,
+        )
+    }
+
+    /// Projects the top of a map position
+    fn project_pos(&self, pos: &Pos, angle: f64) -> (f64, f64, f64) {
+        self.project(
+            pos.x as f64,
+            pos.y as f64,
+            self.map.height(pos) as f64,
+            angle,
+        )
+    }
+
+    /// Calculates the shade of a position lit from the top left of the screen
+    fn shade(&self, x: usize, y: usize, angle: f64) -> u8 {
+        let h = |x: usize, y: usize| self.map.heights[y][x] as f64 * RELIEF;
+
+        // Slope across the map
+        let gx = (h((x + 1).min(self.map.max_x as usize), y) - h(x.saturating_sub(1), y)) / 2.0;
+        let gy = (h(x, (y + 1).min(self.map.max_y as usize)) - h(x, y.saturating_sub(1))) / 2.0;
+
+        // Rotate the slope to the screen
+        let sx = (gx * angle.cos()) - (gy * angle.sin());
+        let sy = (gx * angle.sin()) + (gy * angle.cos());
+
+        // Surface normal dotted with the light direction
+        let (nx, ny, nz) = (-sx, -sy, 1.0);
+        let (lx, ly, lz) = (-0.5, -0.5, std::f64::consts::FRAC_1_SQRT_2);
+
+        let light = ((nx * lx) + (ny * ly) + (nz * lz)) / (nx * nx + ny * ny + nz * nz).sqrt();
+
+        (light.clamp(0.0, 1.0) * (SHADES - 1) as f64).round() as u8
+    }
+}
@@ -9,22 +9,29 @@ fn main() -> Result<(), Box<dyn Error>> {
     let map = Map::new(parse_input_vec(12, input_transform)?);
 
     // Run parts
-    println!("Part 1: {}", part1(&map));
-    println!("Part 2: {}", part2(&map));
+    println!("Part 1: {}", part1(&map).ok_or("No path found")?);
+    println!("Part 2: {}", part2(&map).ok_or("No path found")?);
 
     Ok(())
 }
 
 /// Run part 1
-fn part1(map: &Map) -> usize {
+fn part1(map: &Map) -> Option<usize> {
     // Shortest path from START to END, allowed to go up by 1 only
-    map.shortest_path(map.start(), |n| *n == *map.end(), |from, to| to <= from + 1)
+    map.distance_field(&[map.start().clone()], climb)
+        .distance(map.end())
 }
 
 /// Run part 2
-fn part2(map: &Map) -> usize {
-    // Shortest path from END to height 0, allowed to go down by 1 only
-    map.shortest_path(map.end(), |n| map.height(n) == 0, |from, to| to >= from - 1)
+fn part2(map: &Map) -> Option<usize> {
+    // Shortest path from any position at height 0 to END, allowed to go up by 1 only
+    map.distance_field(&map.positions_at_height(0), climb)
+        .distance(map.end())
+}
+
+/// Allowed to go up by 1 only
+fn climb(from: u8, to: u8) -> bool {
+    to <= from + 1
 }
 
 /// Input parsing (no-op)
@@ -37,6 +44,7 @@ mod tests {
     use aoc::input::parse_test_vec;
 
     use super::*;
+    use day12lib::{Algorithm, CostModel, Pos, Route};
 
     const EXAMPLE1: &str = "Sabqponm
 abcryxxl
@@ -48,7 +56,124 @@ abdefghi
     #[test]
     fn test1() {
         let input = Map::new(parse_test_vec(EXAMPLE1, input_transform).unwrap());
-        assert_eq!(part1(&input), 31);
-        assert_eq!(part2(&input), 29);
+        assert_eq!(part1(&input), Some(31));
+        assert_eq!(part2(&input), Some(29));
+    }
+
+    #[test]
+    fn test_distance_field() {
+        let map = Map::new(parse_test_vec(EXAMPLE1, input_transform).unwrap());
+
+        // Search backwards from END matches the forward search
+        let field = map.distance_field(&[map.end().clone()], |from, to| climb(to, from));
+        assert_eq!(field.distance(map.start()), Some(31));
+        assert_eq!(
+            map.shortest_path(
+                map.end(),
+                |n| map.height(n) == 0,
+                |from, to| climb(to, from)
+            ),
+            Some(29)
+        );
+
+        // Every reachable position has a legal path of the right length
+        for (pos, dist) in field.reachable() {
+            let path = field.path(&pos).unwrap();
+
+            assert_eq!(path.len(), dist + 1);
+            assert_eq!(path[0], *map.end());
+            assert_eq!(path[dist], pos);
+
+            for step in path.windows(2) {
+                assert_eq!(
+                    step[0].x.abs_diff(step[1].x) + step[0].y.abs_diff(step[1].y),
+                    1
+                );
+                assert!(climb(map.height(&step[1]), map.height(&step[0])));
+            }
+        }
+
+        // Unreachable
+        let map = Map::new(parse_test_vec("Sbz\nzzE\n", input_transform).unwrap());
+        let field = map.distance_field(&[map.start().clone()], climb);
+
+        assert_eq!(field.distance(&Pos::new(1, 0)), Some(1));
+        assert_eq!(field.distance(map.end()), None);
+        assert_eq!(field.path(map.end()), None);
+        assert_eq!(field.reachable().count(), 2);
+        assert_eq!(
+            map.shortest_path(map.start(), |n| n == map.end(), climb),
+            None
+        );
+    }
+
+    #[test]
+    fn test_weighted() {
+        let map = Map::new(parse_test_vec(EXAMPLE1, input_transform).unwrap());
+
+        let check_route = |route: &Route, costs: &CostModel| {
+            assert_eq!(route.path[0], *map.start());
+            assert_eq!(route.path[route.path.len() - 1], *map.end());
+
+            let cost = route
+                .path
+                .windows(2)
+                .map(|step| {
+                    costs
+                        .cost(map.height(&step[0]), map.height(&step[1]))
+                        .unwrap()
+                })
+                .sum::<usize>();
+
+            assert_eq!(cost, route.cost);
+        };
+
+        // Unit costs match the breadth first search
+        let unit = CostModel::unit(1);
+
+        for algorithm in [Algorithm::Dijkstra, Algorithm::AStar] {
+            let route = map
+                .cheapest_path(map.start(), map.end(), &unit, algorithm)
+                .unwrap();
+
+            assert_eq!(route.cost, 31);
+            check_route(&route, &unit);
+        }
+
+        // Climbing is expensive and descending is free but limited
+        let hike = CostModel {
+            max_climb: 2,
+            max_descent: 3,
+            step_cost: 1,
+            climb_cost: 4,
+            descent_cost: 0,
+        };
+
+        let dijkstra = map
+            .cheapest_path(map.start(), map.end(), &hike, Algorithm::Dijkstra)
+            .unwrap();
+        let astar = map
+            .cheapest_path(map.start(), map.end(), &hike, Algorithm::AStar)
+            .unwrap();
+
+        assert_eq!(dijkstra.cost, astar.cost);
+        assert!(astar.expanded <= dijkstra.expanded);
+        check_route(&dijkstra, &hike);
+        check_route(&astar, &hike);
+
+        // Unreachable
+        let map = Map::new(parse_test_vec("Sbz\nzzE\n", input_transform).unwrap());
+
+        assert!(map
+            .cheapest_path(map.start(), map.end(), &unit, Algorithm::AStar)
+            .is_none());
+        assert!(map
+            .cheapest_path(
+                map.start(),
+                map.end(),
+                &CostModel::unit(25),
+                Algorithm::AStar
+            )
+            .is_some());
     }
 }
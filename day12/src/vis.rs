@@ -18,7 +18,11 @@ fn main() -> Result<(), Box<dyn Error>> {
     stdout().flush()?;
 
     part2vis(&map)?;
-    println!(" part2");
+    print!(" part2");
+    stdout().flush()?;
+
+    terrainvis(&map)?;
+    println!(" terrain");
 
     Ok(())
 }
@@ -26,6 +30,10 @@ fn main() -> Result<(), Box<dyn Error>> {
 /// Scaling for GIF output
 const GIF_SCALE: u16 = 6;
 
+/// Tile size in pixels and viewing angle in degrees for the terrain view
+const TERRAIN_TILE: u16 = 6;
+const TERRAIN_ANGLE: f64 = 30.0;
+
 /// Generate visualisation for part 1
 fn part1vis(map: &Map) -> Result<(), Box<dyn Error>> {
     // Shortest path from START to END, allowed to go up by 1 only
@@ -52,6 +60,22 @@ fn part2vis(map: &Map) -> Result<(), Box<dyn Error>> {
     )
 }
 
+/// Generate 3D terrain view with the part 1 path
+fn terrainvis(map: &Map) -> Result<(), Box<dyn Error>> {
+    let path = map
+        .distance_field(&[map.start().clone()], |from, to| to <= from + 1)
+        .path(map.end())
+        .ok_or("No path found")?;
+
+    map.terrain_vis(
+        "vis/day12-terrain.gif",
+        Some("vis/day12-terrain-anim.gif"),
+        &path,
+        TERRAIN_TILE,
+        TERRAIN_ANGLE,
+    )
+}
+
 /// Input parsing (no-op)
 fn input_transform(line: String) -> String {
     line
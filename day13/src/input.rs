@@ -0,0 +1,11 @@
+use crate::packet::Packet;
+
+pub type InputEnt = Option<Packet>;
+
+pub fn input_transform(line: String) -> InputEnt {
+    if line.is_empty() {
+        None
+    } else {
+        Some(Packet::parse(&line).unwrap_or_else(|e| panic!("Invalid packet '{line}': {e}")))
+    }
+}
@@ -0,0 +1,158 @@
+use std::fmt;
+
+use crate::packet::Packet;
+
+/// JSON value. Only the arrays and numbers which packets are made of are supported
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Number(f64),
+    Array(Vec<Json>),
+}
+
+impl Json {
+    /// Parses a JSON document made of arrays and numbers. Errors report the byte position
+    /// in the input
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let mut parser = Parser { s, pos: 0 };
+
+        let value = parser.value()?;
+
+        parser.skip_ws();
+
+        if parser.pos != s.len() {
+            Err(parser.error("Unexpected trailing characters"))?
+        }
+
+        Ok(value)
+    }
+}
+
+/// Renders the value as compact JSON
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Json::Number(n) => write!(f, "{n}"),
+            Json::Array(items) => {
+                write!(f, "[")?;
+
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+
+                    write!(f, "{item}")?;
+                }
+
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+impl From<&Packet> for Json {
+    fn from(packet: &Packet) -> Self {
+        match packet {
+            Packet::Number(n) => Json::Number(*n as f64),
+            Packet::List(items) => Json::Array(items.iter().map(Json::from).collect()),
+        }
+    }
+}
+
+/// Converts nested arrays of non-negative integers to a packet
+impl TryFrom<&Json> for Packet {
+    type Error = String;
+
+    fn try_from(json: &Json) -> Result<Self, Self::Error> {
+        match json {
+            Json::Number(n) if n.fract() == 0.0 && *n >= 0.0 && *n <= u32::MAX as f64 => {
+                Ok(Packet::Number(*n as u32))
+            }
+            Json::Array(items) => Ok(Packet::List(
+                items
+                    .iter()
+                    .map(Packet::try_from)
+                    .collect::<Result<Vec<_>, _>>()?,
+            )),
+            _ => Err(format!("{json} can't be converted to a packet")),
+        }
+    }
+}
+
+/// Recursive descent JSON parser
+struct Parser<'a> {
+    s: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn value(&mut self) -> Result<Json, String> {
+        self.skip_ws();
+
+        match self.peek() {
+            Some('[') => self.array(),
+            Some('-' | '0'..='9') => self.number(),
+            Some(_) => Err(self.error("Expected an array or a number")),
+            None => Err(self.error("Unexpected end of input")),
+        }
+    }
+
+    /// Parses comma separated values up to the closing bracket
+    fn array(&mut self) -> Result<Json, String> {
+        // Skip opening bracket
+        self.pos += 1;
+
+        let mut items = Vec::new();
+
+        self.skip_ws();
+
+        if self.peek() == Some(']') {
+            self.pos += 1;
+            return Ok(Json::Array(items));
+        }
+
+        loop {
+            items.push(self.value()?);
+
+            self.skip_ws();
+
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some(']') => {
+                    self.pos += 1;
+                    break Ok(Json::Array(items));
+                }
+                _ => Err(self.error("Expected ',' or ']'"))?,
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<Json, String> {
+        let start = self.pos;
+
+        while let Some('-' | '+' | '.' | 'e' | 'E' | '0'..='9') = self.peek() {
+            self.pos += 1;
+        }
+
+        // Numbers too large for a float parse as infinity
+        self.s[start..self.pos]
+            .parse::<f64>()
+            .ok()
+            .filter(|n| n.is_finite())
+            .map(Json::Number)
+            .ok_or_else(|| format!("Invalid number at position {start}"))
+    }
+
+    fn skip_ws(&mut self) {
+        while let Some(' ' | '\t' | '\n' | '\r') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.s[self.pos..].chars().next()
+    }
+
+    fn error(&self, msg: &str) -> String {
+        format!("{msg} at position {}", self.pos)
+    }
+}
@@ -0,0 +1,5 @@
+pub mod input;
+pub mod json;
+pub mod packet;
+pub mod rank;
+pub mod stream;
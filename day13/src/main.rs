@@ -3,6 +3,10 @@ use std::error::Error;
 
 use aoc::input::parse_input_vec;
 
+use day13lib::input::{input_transform, InputEnt};
+use day13lib::packet::Packet;
+use day13lib::rank::divider_positions;
+
 fn main() -> Result<(), Box<dyn Error>> {
     // Get input
     let input = parse_input_vec(13, input_transform)?;
@@ -27,183 +31,22 @@ fn part1(input: &[InputEnt]) -> usize {
 }
 
 fn part2(input: &[InputEnt]) -> usize {
-    // Get references to all lists (ignoring separators)
-    let mut lists: Vec<&List> = input.iter().filter_map(|ent| ent.as_ref()).collect();
-
-    // Create dividers
-    let divider1: List = List {
-        items: vec![ListItem::List(List {
-            items: vec![ListItem::Number(2)],
-        })],
-    };
-
-    let divider2: List = List {
-        items: vec![ListItem::List(List {
-            items: vec![ListItem::Number(6)],
-        })],
-    };
-
-    // Add dividers to the list
-    lists.push(&divider1);
-    lists.push(&divider2);
-
-    // Sort the list
-    lists.sort();
-
-    // Get positions of the dividers
-    let div1pos = lists
-        .iter()
-        .position(|&x| *x == divider1)
-        .expect("Divider 1 should be present")
-        + 1;
+    // Find the divider positions without sorting
+    let dividers = [Packet::divider(2), Packet::divider(6)];
 
-    let div2pos = lists
+    divider_positions(input.iter().flatten(), &dividers)
         .iter()
-        .position(|&x| *x == divider2)
-        .expect("Divider 2 should be present")
-        + 1;
-
-    // Return result
-    div1pos * div2pos
-}
-
-#[derive(Debug, Default)]
-struct List {
-    items: Vec<ListItem>,
-}
-
-// Convert a single u16 to a List containing a single number
-impl From<u16> for List {
-    fn from(value: u16) -> Self {
-        List {
-            items: vec![ListItem::Number(value)],
-        }
-    }
-}
-
-impl PartialEq for List {
-    fn eq(&self, other: &Self) -> bool {
-        self.cmp(other) == Ordering::Equal
-    }
-}
-
-impl Eq for List {}
-
-impl PartialOrd for List {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-impl Ord for List {
-    fn cmp(&self, other: &Self) -> Ordering {
-        let mut ord = Ordering::Equal;
-
-        // Compare each list item in turn
-        for pair in self.items.iter().zip(other.items.iter()) {
-            ord = match pair {
-                (ListItem::Number(a), ListItem::Number(b)) => (*a).cmp(b),
-                (ListItem::Number(a), ListItem::List(b)) => List::from(*a).cmp(b),
-                (ListItem::List(a), ListItem::Number(b)) => (*a).cmp(&List::from(*b)),
-                (ListItem::List(a), ListItem::List(b)) => (*a).cmp(b),
-            };
-
-            if ord != Ordering::Equal {
-                // Found a difference
-                break;
-            }
-        }
-
-        if ord == Ordering::Equal {
-            // No difference found - lists with fewer items sort first
-            ord = self.items.len().cmp(&other.items.len())
-        }
-
-        ord
-    }
-}
-
-#[derive(Debug)]
-enum ListItem {
-    Number(u16),
-    List(List),
-}
-
-// Input parsing
-
-type InputEnt = Option<List>;
-
-fn input_transform(line: String) -> InputEnt {
-    let mut list = None;
-
-    if !line.is_empty() {
-        let mut list_stack: Vec<List> = Vec::new();
-        let mut num_start = 0;
-
-        // Flush a number to the current list
-        let flush_num = |num_start: &mut usize, next: usize, list_stack: &mut Vec<List>| {
-            if *num_start != 0 {
-                let index = list_stack.len() - 1;
-
-                list_stack[index].items.push(ListItem::Number(
-                    line[*num_start..next]
-                        .parse::<u16>()
-                        .expect("Should be parseable u16"),
-                ));
-
-                *num_start = 0;
-            }
-        };
-
-        for (i, c) in line.chars().enumerate() {
-            match c {
-                '[' => {
-                    // New list
-                    list_stack.push(List::default());
-                }
-                ']' => {
-                    // End of list
-                    flush_num(&mut num_start, i, &mut list_stack);
-
-                    if list_stack.len() == 1 {
-                        // End of main list - finished
-                        list = Some(
-                            list_stack
-                                .pop()
-                                .expect("Should be a list left on the stack"),
-                        );
-
-                        break;
-                    } else {
-                        // End of sub list
-                        let sub_list = list_stack.pop().expect("Should be a list on the stack");
-                        let index = list_stack.len() - 1;
-
-                        list_stack[index].items.push(ListItem::List(sub_list));
-                    }
-                }
-                '0'..='9' => {
-                    // Number
-                    if num_start == 0 {
-                        num_start = i;
-                    }
-                }
-                ',' => {
-                    // Value separator
-                    flush_num(&mut num_start, i, &mut list_stack);
-                }
-                c => panic!("Unexpected character {c} in input"),
-            }
-        }
-    }
-
-    list
+        .product()
 }
 
 #[cfg(test)]
 mod tests {
     use aoc::input::parse_test_vec;
 
+    use day13lib::json::Json;
+    use day13lib::rank::rank;
+    use day13lib::stream::compare;
+
     use super::*;
 
     const EXAMPLE1: &str = "[1,1,3,1,1]
@@ -237,4 +80,157 @@ mod tests {
         assert_eq!(part1(&input), 13);
         assert_eq!(part2(&input), 140);
     }
+
+    #[test]
+    fn test_rank() {
+        let input = parse_test_vec(EXAMPLE1, input_transform).unwrap();
+        let packets = input.iter().flatten().collect::<Vec<_>>();
+
+        // Rank of each packet matches its sorted position
+        let mut sorted = packets.clone();
+        sorted.sort();
+
+        for (i, packet) in sorted.iter().enumerate() {
+            assert_eq!(rank(packets.iter().copied(), packet), i + 1);
+        }
+
+        assert_eq!(rank(packets.iter().copied(), &Packet::divider(2)), 10);
+        assert_eq!(rank(packets.iter().copied(), &Packet::divider(6)), 13);
+
+        // Dividers in any order, including some equal to packets and each other
+        let dividers =
+            ["[[6]]", "[9]", "[[2]]", "[]", "[[6]]", "[[[]]]"].map(|d| Packet::parse(d).unwrap());
+
+        let positions = divider_positions(packets.iter().copied(), &dividers);
+
+        let mut all = packets.clone();
+        all.extend(dividers.iter());
+        all.sort();
+
+        for (divider, pos) in dividers.iter().zip(positions) {
+            assert_eq!(all.iter().position(|p| *p == divider).unwrap() + 1, pos);
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        // Round trip every example packet
+        for line in EXAMPLE1.lines().filter(|l| !l.is_empty()) {
+            assert_eq!(Packet::parse(line).unwrap().to_string(), line);
+        }
+
+        assert_eq!(
+            "[1,[10000000000]]".parse::<Packet>().unwrap_err(),
+            "Number too large at position 4"
+        );
+        assert_eq!(
+            Packet::parse("[1,,2]").unwrap_err(),
+            "Expected a number or '[' at position 3"
+        );
+        assert_eq!(
+            Packet::parse("[[1] 2]").unwrap_err(),
+            "Expected ',' or ']' at position 4"
+        );
+        assert_eq!(
+            Packet::parse("[1,[2]").unwrap_err(),
+            "Expected ',' or ']' at position 6"
+        );
+        assert_eq!(
+            Packet::parse("[1]]").unwrap_err(),
+            "Unexpected trailing characters at position 3"
+        );
+    }
+
+    #[test]
+    fn test_json() {
+        let packet = Packet::parse("[1,[2,[3,[]]],4000000000]").unwrap();
+        let json = Json::from(&packet);
+
+        assert_eq!(json.to_string(), packet.to_string());
+        assert_eq!(
+            Packet::try_from(&json).unwrap().to_string(),
+            packet.to_string()
+        );
+
+        // Generic JSON with whitespace
+        let json = Json::parse(" [ 1 , [ ] , [ 2.0 , 3e1 ] ] ").unwrap();
+        assert_eq!(
+            Packet::try_from(&json).unwrap().to_string(),
+            "[1,[],[2,30]]"
+        );
+
+        // Values which aren't packets
+        let json = Json::parse("[1, -2]").unwrap();
+        assert_eq!(json.to_string(), "[1,-2]");
+        assert_eq!(
+            Packet::try_from(&json).unwrap_err(),
+            "-2 can't be converted to a packet"
+        );
+        assert!(Packet::try_from(&Json::Number(1.5)).is_err());
+
+        // Unsupported and out of range values
+        assert_eq!(
+            Json::parse(r#"[1, "a"]"#).unwrap_err(),
+            "Expected an array or a number at position 4"
+        );
+        assert_eq!(
+            Json::parse("[1e999]").unwrap_err(),
+            "Invalid number at position 1"
+        );
+
+        assert_eq!(
+            Json::parse("[1, 2").unwrap_err(),
+            "Expected ',' or ']' at position 5"
+        );
+    }
+
+    #[test]
+    fn test_stream_compare() {
+        // Example pairs
+        let input = parse_test_vec(EXAMPLE1, input_transform).unwrap();
+        let lines = EXAMPLE1.lines().collect::<Vec<_>>();
+
+        for (packets, lines) in input.chunks(3).zip(lines.chunks(3)) {
+            assert_eq!(compare(lines[0], lines[1]), packets[0].cmp(&packets[1]));
+        }
+
+        assert_eq!(compare("[[[[5]]]]", "[5]"), Ordering::Equal);
+        assert_eq!(compare("[[5],[]]", "[5,[[]]]"), Ordering::Less);
+        assert_eq!(compare("[10]", "[[1,0]]"), Ordering::Greater);
+        assert_eq!(compare("[9]", "[10]"), Ordering::Less);
+        assert_eq!(compare("[4294967295]", "[4294967294]"), Ordering::Greater);
+
+        // Pseudo random packets with small numbers so comparisons go deep, including
+        // multi-digit numbers against their digits in a list
+        let mut rand = rng(12345);
+
+        fn random_packet(rand: &mut impl FnMut(u32) -> u32, depth: usize) -> Packet {
+            if depth > 0 && rand(3) == 0 {
+                Packet::Number(rand(21))
+            } else {
+                let len = if depth < 4 { rand(4) } else { 0 };
+
+                Packet::List((0..len).map(|_| random_packet(rand, depth + 1)).collect())
+            }
+        }
+
+        let packets = (0..300)
+            .map(|_| random_packet(&mut rand, 0))
+            .collect::<Vec<_>>();
+        let text = packets.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+
+        for (a, ta) in packets.iter().zip(&text) {
+            for (b, tb) in packets.iter().zip(&text) {
+                assert_eq!(compare(ta, tb), a.cmp(b), "{ta} <=> {tb}");
+            }
+        }
+    }
+
+    /// Returns a repeatable pseudo random number generator giving numbers below a limit
+    fn rng(mut seed: u32) -> impl FnMut(u32) -> u32 {
+        move |n| {
+            seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+            (seed >> 16) % n
+        }
+    }
 }
@@ -0,0 +1,157 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+/// Packet value, either a number or a list of values
+#[derive(Debug, Clone)]
+pub enum Packet {
+    Number(u32),
+    List(Vec<Packet>),
+}
+
+impl Packet {
+    /// Parses a packet. Errors report the byte position in the input
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let mut parser = Parser {
+            bytes: s.as_bytes(),
+            pos: 0,
+        };
+
+        let packet = parser.value()?;
+
+        if parser.pos != parser.bytes.len() {
+            Err(parser.error("Unexpected trailing characters"))?
+        }
+
+        Ok(packet)
+    }
+
+    /// Creates a divider packet containing a number in a list in a list
+    pub fn divider(n: u32) -> Self {
+        Packet::List(vec![Packet::List(vec![Packet::Number(n)])])
+    }
+}
+
+impl FromStr for Packet {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// Renders the packet in the input syntax
+impl fmt::Display for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Packet::Number(n) => write!(f, "{n}"),
+            Packet::List(items) => {
+                write!(f, "[")?;
+
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+
+                    write!(f, "{item}")?;
+                }
+
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+impl PartialEq for Packet {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Packet {}
+
+impl PartialOrd for Packet {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Packet {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Packet::Number(a), Packet::Number(b)) => a.cmp(b),
+            // Compare a number with a list by converting the number to a list
+            (Packet::Number(a), Packet::List(_)) => {
+                Packet::List(vec![Packet::Number(*a)]).cmp(other)
+            }
+            (Packet::List(_), Packet::Number(b)) => {
+                self.cmp(&Packet::List(vec![Packet::Number(*b)]))
+            }
+            // Compare each list item in turn. If no difference is found then lists
+            // with fewer items sort first
+            (Packet::List(a), Packet::List(b)) => a.iter().cmp(b.iter()),
+        }
+    }
+}
+
+/// Recursive descent packet parser
+struct Parser<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    /// value := number | '[' (value (',' value)*)? ']'
+    fn value(&mut self) -> Result<Packet, String> {
+        match self.peek() {
+            Some(b'[') => {
+                self.pos += 1;
+
+                let mut items = Vec::new();
+
+                if self.peek() == Some(b']') {
+                    self.pos += 1;
+                } else {
+                    loop {
+                        items.push(self.value()?);
+
+                        match self.peek() {
+                            Some(b',') => self.pos += 1,
+                            Some(b']') => {
+                                self.pos += 1;
+                                break;
+                            }
+                            _ => Err(self.error("Expected ',' or ']'"))?,
+                        }
+                    }
+                }
+
+                Ok(Packet::List(items))
+            }
+            Some(b'0'..=b'9') => {
+                let start = self.pos;
+
+                while let Some(b'0'..=b'9') = self.peek() {
+                    self.pos += 1;
+                }
+
+                // Digits are ASCII so this can only fail on overflow
+                std::str::from_utf8(&self.bytes[start..self.pos])
+                    .unwrap()
+                    .parse::<u32>()
+                    .map(Packet::Number)
+                    .map_err(|_| format!("Number too large at position {start}"))
+            }
+            Some(_) => Err(self.error("Expected a number or '['")),
+            None => Err(self.error("Unexpected end of packet")),
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn error(&self, msg: &str) -> String {
+        format!("{msg} at position {}", self.pos)
+    }
+}
@@ -0,0 +1,22 @@
+use crate::packet::Packet;
+
+/// Returns the 1-based position a packet would have if it was added to a set of
+/// packets and they were sorted. Equal packets sort after it
+pub fn rank<'a, I>(packets: I, packet: &Packet) -> usize
+where
+    I: IntoIterator<Item = &'a Packet>,
+{
+    packets.into_iter().filter(|p| *p < packet).count() + 1
+}
+
+/// Returns the 1-based positions of divider packets after adding them to a set of
+/// packets and sorting, by counting the packets less than each divider
+pub fn divider_positions<'a, I>(packets: I, dividers: &[Packet]) -> Vec<usize>
+where
+    I: IntoIterator<Item = &'a Packet> + Clone,
+{
+    dividers
+        .iter()
+        .map(|divider| rank(packets.clone(), divider) + rank(dividers, divider) - 1)
+        .collect()
+}
@@ -0,0 +1,105 @@
+use std::cmp::Ordering;
+
+/// Compares two packets directly from their text without building trees or allocating.
+/// Packets must have passed `Packet::parse`, and this panics on anything it would reject
+pub fn compare(left: &str, right: &str) -> Ordering {
+    let mut left = Tokens::new(left);
+    let mut right = Tokens::new(right);
+
+    loop {
+        match (left.next(), right.next()) {
+            (None, None) => break Ordering::Equal,
+            (Some(Token::Open), Some(Token::Open)) | (Some(Token::Close), Some(Token::Close)) => (),
+            (Some(Token::Number(a)), Some(Token::Number(b))) => {
+                if a != b {
+                    break a.cmp(&b);
+                }
+            }
+            // Compare a number with a list by treating the number as a list
+            (Some(Token::Number(a)), Some(Token::Open)) => left.wrap(a),
+            (Some(Token::Open), Some(Token::Number(b))) => right.wrap(b),
+            // Left list ran out of items first
+            (Some(Token::Close), _) | (None, _) => break Ordering::Less,
+            // Right list ran out of items first
+            (_, Some(Token::Close)) | (_, None) => break Ordering::Greater,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    Open,
+    Close,
+    Number(u32),
+}
+
+/// Packet tokeniser which can put back a number as a list containing the number
+struct Tokens<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    /// Number put back to be returned next
+    wrapped: Option<u32>,
+    /// Closing brackets to return after the wrapped number
+    closes: usize,
+}
+
+impl<'a> Tokens<'a> {
+    fn new(packet: &'a str) -> Self {
+        Self {
+            bytes: packet.as_bytes(),
+            pos: 0,
+            wrapped: None,
+            closes: 0,
+        }
+    }
+
+    /// Puts back a number just returned as if it were in a list whose opening
+    /// bracket has already been returned
+    fn wrap(&mut self, n: u32) {
+        self.wrapped = Some(n);
+        self.closes += 1;
+    }
+}
+
+impl Iterator for Tokens<'_> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(n) = self.wrapped.take() {
+            return Some(Token::Number(n));
+        }
+
+        if self.closes > 0 {
+            self.closes -= 1;
+            return Some(Token::Close);
+        }
+
+        // Skip separators
+        while self.bytes.get(self.pos) == Some(&b',') {
+            self.pos += 1;
+        }
+
+        let token = match self.bytes.get(self.pos)? {
+            b'[' => Token::Open,
+            b']' => Token::Close,
+            b'0'..=b'9' => {
+                let mut n = 0u32;
+
+                while let Some(d @ b'0'..=b'9') = self.bytes.get(self.pos) {
+                    n = n
+                        .checked_mul(10)
+                        .and_then(|n| n.checked_add((d - b'0') as u32))
+                        .expect("Number too large in packet");
+                    self.pos += 1;
+                }
+
+                return Some(Token::Number(n));
+            }
+            c => panic!("Unexpected character '{}' in packet", *c as char),
+        };
+
+        self.pos += 1;
+
+        Some(token)
+    }
+}
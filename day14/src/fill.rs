@@ -0,0 +1,49 @@
+use super::{FallRules, Map, Tile};
+
+impl Map {
+    /// Fills the map with the pile left once all sources are blocked without dropping
+    /// individual grains. Working down from the top, a position fills if it isn't rock
+    /// and it's a source or any of the three positions above it is filled. Only valid
+    /// with the classic fall rules and a floor. Returns the number of grains at rest
+    pub fn fill(&mut self) -> Option<usize> {
+        if self.rules != FallRules::classic() {
+            return None;
+        }
+
+        let floor = self.floor?;
+        let mut prev = vec![false; self.width];
+
+        for y in 0..floor {
+            let mut cur = vec![false; self.width];
+
+            for (x, filled) in cur.iter_mut().enumerate() {
+                *filled = match self.content[y][x] {
+                    Tile::Rock => false,
+                    Tile::Sand => true,
+                    Tile::Empty => {
+                        prev[x.saturating_sub(1)..=(x + 1).min(self.width - 1)]
+                            .iter()
+                            .any(|p| *p)
+                            || self.sources.contains(&(x + self.x_offset, y))
+                    }
+                };
+
+                if *filled {
+                    self.content[y][x] = Tile::Sand;
+                }
+            }
+
+            prev = cur;
+        }
+
+        // Paths followed by earlier grains are checked again on the next drop
+        self.grains = self
+            .content
+            .iter()
+            .flatten()
+            .filter(|t| matches!(t, Tile::Sand))
+            .count();
+
+        Some(self.grains)
+    }
+}
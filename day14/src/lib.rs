@@ -1,7 +1,15 @@
 use std::cmp::{max, min};
 
+mod fill;
+mod rules;
+mod timeline;
+
+pub use rules::FallRules;
+pub use timeline::{Grain, Timeline};
+
 const SAND_X: usize = 500;
 const BORDER: usize = 2;
+const FLOOR_DEPTH: usize = 2;
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum Tile {
@@ -10,11 +18,50 @@ pub enum Tile {
     Sand,
 }
 
+/// Simulation configuration
+#[derive(Debug, Clone)]
+pub struct SandConfig {
+    /// Positions sand is dropped from
+    pub sources: Vec<(usize, usize)>,
+    /// Moves a falling grain tries
+    pub rules: FallRules,
+    /// Depth of the floor below the lowest rock, if any
+    pub floor: Option<usize>,
+}
+
+impl Default for SandConfig {
+    fn default() -> Self {
+        Self {
+            sources: vec![(SAND_X, 0)],
+            rules: FallRules::default(),
+            floor: None,
+        }
+    }
+}
+
 pub struct Map {
     pub width: usize,
     pub height: usize,
     pub x_offset: usize,
-    pub content: Vec<Vec<Tile>>,
+    content: Vec<Vec<Tile>>,
+    sources: Vec<(usize, usize)>,
+    rules: FallRules,
+    /// Y coordinate of the floor, if any
+    floor: Option<usize>,
+    next_source: usize,
+    /// Path of the last grain dropped from each source
+    paths: Vec<Vec<(usize, usize)>>,
+    /// Grains at rest in the map when each path was last followed
+    path_grains: Vec<usize>,
+    /// Number of grains at rest in the map
+    grains: usize,
+}
+
+/// Result of dropping a grain. The path followed is left on the source's path stack
+enum Fall {
+    Full,
+    Out,
+    Rest,
 }
 
 pub enum DropResult {
@@ -25,26 +72,50 @@ pub enum DropResult {
 
 impl Map {
     /// Create new map, optionally with a floor
-    pub fn new(input: &[InputEnt], floor: bool) -> Self {
+    pub fn new(input: &[InputEnt], floor: bool) -> Result<Self, String> {
+        Self::with_config(
+            input,
+            &SandConfig {
+                floor: floor.then_some(FLOOR_DEPTH),
+                ..SandConfig::default()
+            },
+        )
+    }
+
+    /// Create new map from a configuration. The floor extends as far as sand can
+    /// fall diagonally from the sources. Fails if there are no sources or the map
+    /// would extend past x = 0
+    pub fn with_config(input: &[InputEnt], config: &SandConfig) -> Result<Self, String> {
+        if config.sources.is_empty() {
+            Err("No sand sources")?
+        }
+
         // Work out min x, max x and max y (min y is 0)
-        let (mut min_x, mut max_x, mut max_y) =
-            input
-                .iter()
-                .fold((usize::MAX, 0, 0), |(min_x, max_x, max_y), l| {
-                    (
-                        min(min_x, *l.iter().map(|(x, _)| x).min().unwrap()),
-                        max(max_x, *l.iter().map(|(x, _)| x).max().unwrap()),
-                        max(max_y, *l.iter().map(|(_, y)| y).max().unwrap()),
-                    )
-                });
+        let (mut min_x, mut max_x, mut max_y) = input
+            .iter()
+            .flatten()
+            .chain(config.sources.iter())
+            .fold((usize::MAX, 0, 0), |(min_x, max_x, max_y), (x, y)| {
+                (min(min_x, *x), max(max_x, *x), max(max_y, *y))
+            });
 
         // Adjust for when adding the floor
-        if floor {
-            max_y += 2;
-            min_x = SAND_X - max_y;
-            max_x = SAND_X + max_y;
+        if let Some(depth) = config.floor {
+            max_y += depth;
+
+            for (x, y) in &config.sources {
+                min_x = min(min_x, x.saturating_sub(max_y - y));
+                max_x = max(max_x, x + (max_y - y));
+            }
         };
 
+        // Leave room for the border
+        if min_x < BORDER {
+            Err(format!(
+                "Map would extend past x = 0 (minimum x is {BORDER})"
+            ))?
+        }
+
         // Work out dimensions
         let height = max_y + BORDER;
         let width = (max_x - min_x) + (BORDER * 2);
@@ -58,6 +129,13 @@ impl Map {
             height,
             x_offset: min_x - BORDER,
             content,
+            sources: config.sources.clone(),
+            rules: config.rules.clone(),
+            floor: config.floor.map(|_| max_y),
+            next_source: 0,
+            paths: vec![vec![]; config.sources.len()],
+            path_grains: vec![0; config.sources.len()],
+            grains: 0,
         };
 
         // Draw the rock lines
@@ -71,47 +149,176 @@ impl Map {
         }
 
         // Add the floor
-        if floor {
+        if config.floor.is_some() {
             result.line(min_x, max_y, max_x, max_y)
         }
 
-        result
+        Ok(result)
+    }
+
+    /// Returns the tiles of the map by row. Tiles can only be changed by dropping sand
+    /// so the paths followed by previous grains stay valid
+    pub fn content(&self) -> &[Vec<Tile>] {
+        &self.content
     }
 
-    /// Drops a particle of sand in to the map
+    /// Returns the positions sand is dropped from
+    pub fn sources(&self) -> &[(usize, usize)] {
+        &self.sources
+    }
+
+    /// Drops grains of sand until one falls out of the map or all sources are blocked
+    pub fn simulate(&mut self) -> Timeline {
+        let mut timeline = Timeline::default();
+
+        loop {
+            let (source, fall) = self.drop_next();
+
+            match fall {
+                Fall::Full => break,
+                Fall::Out => {
+                    timeline.overflow = Some(source);
+                    break;
+                }
+                Fall::Rest => {
+                    let path = &self.paths[source];
+
+                    timeline.grains.push(Grain {
+                        source,
+                        pos: *path.last().unwrap(),
+                        moves: path.len() - 1,
+                    })
+                }
+            }
+        }
+
+        timeline
+    }
+
+    /// Drops a particle of sand from the next source in turn which isn't blocked
     pub fn drop_sand(&mut self) -> DropResult {
-        let mut x = SAND_X;
-        let mut y = 0;
-        let mut path = Vec::with_capacity(self.height);
+        let (source, fall) = self.drop_next();
+
+        self.drop_result(source, fall)
+    }
 
-        if self.get_tile(x, y) == Tile::Sand {
-            return DropResult::Full;
+    /// Drops a particle of sand from a source in to the map
+    pub fn drop_sand_from(&mut self, source: usize) -> DropResult {
+        let fall = self.fall(source);
+
+        self.drop_result(source, fall)
+    }
+
+    /// Drops a particle of sand from the next source in turn which isn't blocked,
+    /// returning the source index with the result
+    fn drop_next(&mut self) -> (usize, Fall) {
+        for _ in 0..self.sources.len() {
+            let source = self.next_source;
+            self.next_source = (source + 1) % self.sources.len();
+
+            match self.fall(source) {
+                Fall::Full => (),
+                fall => return (source, fall),
+            }
+        }
+
+        (self.next_source, Fall::Full)
+    }
+
+    /// Converts a fall to a result containing a copy of the path followed
+    fn drop_result(&self, source: usize, fall: Fall) -> DropResult {
+        match fall {
+            Fall::Full => DropResult::Full,
+            Fall::Out => DropResult::Out(self.paths[source].clone()),
+            Fall::Rest => DropResult::Rest(self.paths[source].clone()),
         }
+    }
+
+    /// Drops a particle of sand from a source. Rather than starting from the source
+    /// each time, the grain follows the path of the previous grain from the source
+    /// as far as the last position which is still open
+    fn fall(&mut self, source: usize) -> Fall {
+        let mut path = std::mem::take(&mut self.paths[source]);
+
+        if self.path_grains[source] == self.grains {
+            // Only the grain at the end of the path has come to rest since the path was followed
+            if path
+                .last()
+                .is_some_and(|(x, y)| !self.tile_is_empty(*x, *y))
+            {
+                path.pop();
+            }
+        } else {
+            // Other grains may have come to rest on the path
+            if let Some(blocked) = path.iter().position(|(x, y)| !self.tile_is_empty(*x, *y)) {
+                path.truncate(blocked);
+            }
+        }
+
+        let result = self.fall_from(&mut path, source);
 
-        while y < self.height - 1 {
+        self.paths[source] = path;
+        self.path_grains[source] = self.grains;
+
+        result
+    }
+
+    /// Continues a grain's fall from the end of a path, or from the source if the path is empty
+    fn fall_from(&mut self, path: &mut Vec<(usize, usize)>, source: usize) -> Fall {
+        let (mut x, mut y) = match path.pop() {
+            Some(pos) => pos,
+            None => {
+                let (x, y) = self.sources[source];
+
+                if !self.tile_is_empty(x, y) {
+                    return Fall::Full;
+                }
+
+                (x, y)
+            }
+        };
+
+        // Start of the positions visited on the current row
+        let mut row_start = path
+            .iter()
+            .rposition(|(_, py)| *py != y)
+            .map_or(0, |i| i + 1);
+
+        'fall: loop {
             path.push((x, y));
 
-            if self.tile_is_empty(x, y + 1) {
-                // Go down
-                y += 1;
-            } else if self.tile_is_empty(x - 1, y + 1) {
-                // Go down and left
-                y += 1;
-                x -= 1;
-            } else if self.tile_is_empty(x + 1, y + 1) {
-                // Go down and right
-                y += 1;
-                x += 1;
-            } else {
-                // Come to rest
-                self.set_tile(x, y, Tile::Sand);
-                return DropResult::Rest(path);
+            for (dx, dy) in &self.rules.moves {
+                let (nx, ny) = (x as isize + dx, y + dy);
+
+                if !self.in_map(nx, ny) {
+                    // Fallen off of the map
+                    return Fall::Out;
+                }
+
+                let nx = nx as usize;
+
+                // Positions already visited on this row can't be moved to again
+                if self.tile_is_empty(nx, ny) && !path[row_start..].contains(&(nx, ny)) {
+                    if ny != y {
+                        row_start = path.len();
+                    }
+
+                    (x, y) = (nx, ny);
+                    continue 'fall;
+                }
             }
+
+            // Come to rest
+            self.set_tile(x, y, Tile::Sand);
+            self.grains += 1;
+
+            return Fall::Rest;
         }
+    }
 
-        // Fallen off of the map
-        path.push((x, y));
-        DropResult::Out(path)
+    /// Return true if the coordinate is within the map
+    fn in_map(&self, x: isize, y: usize) -> bool {
+        x >= self.x_offset as isize && x < (self.x_offset + self.width) as isize && y < self.height
     }
 
     /// Get tile at coordinate
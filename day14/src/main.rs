@@ -1,55 +1,325 @@
 use std::error::Error;
 
 use aoc::input::parse_input_vec;
-use day14lib::{input_transform, DropResult, InputEnt, Map};
+use day14lib::{input_transform, InputEnt, Map};
 
 fn main() -> Result<(), Box<dyn Error>> {
     // Get input
     let input = parse_input_vec(14, input_transform)?;
 
     // Run parts
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
+    println!("Part 1: {}", part1(&input)?);
+    println!("Part 2: {}", part2(&input)?);
 
     Ok(())
 }
 
-fn part1(input: &[InputEnt]) -> usize {
-    do_part(input, false)
+fn part1(input: &[InputEnt]) -> Result<usize, String> {
+    // Create the map and drop sand counting how many come to rest
+    Ok(Map::new(input, false)?.simulate().len())
 }
 
-fn part2(input: &[InputEnt]) -> usize {
-    do_part(input, true)
-}
-
-fn do_part(input: &[InputEnt], floor: bool) -> usize {
-    // Create the map
-    let mut map = Map::new(input, floor);
-
-    // Drop sand counting how many fall
-    let mut count = 0;
-
-    while matches!(map.drop_sand(), DropResult::Rest(_)) {
-        count += 1;
-    }
-
-    count
+fn part2(input: &[InputEnt]) -> Result<usize, String> {
+    // Create the map with a floor and fill it with sand
+    Ok(Map::new(input, true)?.fill().expect("Map has a floor"))
 }
 
 #[cfg(test)]
 mod tests {
     use aoc::input::parse_test_vec;
 
+    use day14lib::{DropResult, FallRules, SandConfig};
+
     use super::*;
 
     const EXAMPLE1: &str = "498,4 -> 498,6 -> 496,6
 503,4 -> 502,4 -> 502,9 -> 494,9
 ";
 
+    const CUP: &str = "494,2 -> 494,6 -> 506,6 -> 506,2
+";
+
     #[test]
     fn test1() {
         let input = parse_test_vec(EXAMPLE1, input_transform).unwrap();
-        assert_eq!(part1(&input), 24);
-        assert_eq!(part2(&input), 93);
+        assert_eq!(part1(&input).unwrap(), 24);
+        assert_eq!(part2(&input).unwrap(), 93);
+    }
+
+    #[test]
+    fn test_config() {
+        let input = parse_test_vec(EXAMPLE1, input_transform).unwrap();
+
+        let run = |config: SandConfig| {
+            let mut map = Map::with_config(&input, &config).unwrap();
+            let timeline = map.simulate();
+
+            // Every grain should be at rest in the map
+            for (x, y) in timeline.rest_positions() {
+                assert!(!map.tile_is_empty(x, y));
+            }
+
+            timeline
+        };
+
+        // Floor at an arbitrary depth
+        let timeline = run(SandConfig {
+            floor: Some(4),
+            ..SandConfig::default()
+        });
+        assert_eq!(timeline.len(), 133);
+        assert_eq!(timeline.overflow, None);
+        assert_eq!(timeline.growth().last(), Some(&(133, 0)));
+
+        // Two sources
+        let timeline = run(SandConfig {
+            sources: vec![(500, 0), (496, 2)],
+            ..SandConfig::default()
+        });
+        assert_eq!(timeline.len(), 3);
+        assert_eq!(timeline.overflow, Some(1));
+
+        let timeline = run(SandConfig {
+            sources: vec![(500, 0), (496, 2)],
+            floor: Some(2),
+            ..SandConfig::default()
+        });
+        assert_eq!(timeline.len(), 110);
+        assert_eq!(timeline.grains.iter().filter(|g| g.source == 1).count(), 40);
+        assert_eq!(timeline.overflow, None);
+
+        // Sliding sand fills a cup flat before running over the side
+        let cup = parse_test_vec(CUP, input_transform).unwrap();
+
+        let mut map = Map::with_config(
+            &cup,
+            &SandConfig {
+                rules: FallRules::sliding(),
+                ..SandConfig::default()
+            },
+        )
+        .unwrap();
+        let timeline = map.simulate();
+        assert_eq!(timeline.len(), 44);
+        assert_eq!(timeline.overflow, Some(0));
+        assert_eq!(timeline.growth(), vec![(1, 5), (12, 4), (23, 3), (34, 2)]);
+
+        assert_eq!(Map::new(&cup, false).unwrap().simulate().len(), 36);
+
+        // Maps which would extend past x = 0 are rejected
+        let ledge = parse_test_vec("3,10 -> 8,10\n", input_transform).unwrap();
+        let config = |sources, floor| SandConfig {
+            sources,
+            floor,
+            ..SandConfig::default()
+        };
+
+        assert!(Map::with_config(&ledge, &config(vec![(3, 0)], None)).is_ok());
+        assert!(Map::with_config(&ledge, &config(vec![(3, 0)], Some(2))).is_err());
+        assert!(Map::with_config(&ledge, &config(vec![(1, 0)], None)).is_err());
+        assert!(Map::with_config(&ledge, &config(vec![], None)).is_err());
+
+        let map = Map::with_config(&ledge, &config(vec![(14, 0), (9, 5)], Some(2))).unwrap();
+        assert_eq!(map.sources(), [(14, 0), (9, 5)]);
+        assert_eq!(map.x_offset, 0);
+    }
+
+    #[test]
+    fn test_fill() {
+        let mut rnd = rng(1);
+
+        for i in 0..100 {
+            // Generate a random cave, every other one at the left edge of the coordinates
+            let left = i % 2 == 1;
+            let base = if left { 2 } else { 480 };
+
+            let cave = (0..rnd(30) + 1)
+                .map(|_| {
+                    let (x, y) = (base + rnd(40), 1 + rnd(40));
+                    let len = rnd(10);
+
+                    if rnd(2) == 0 {
+                        vec![(x, y), (x + len, y)]
+                    } else {
+                        vec![(x, y), (x, y + len)]
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            let floor = 1 + rnd(5);
+
+            // At the left edge the floor only just reaches far enough for the first source
+            let source = if left {
+                cave.iter().flatten().map(|(_, y)| y).max().unwrap() + floor + 2
+            } else {
+                500
+            };
+
+            let config = SandConfig {
+                sources: vec![(source, 0), (source + rnd(20), rnd(5))],
+                floor: Some(floor),
+                ..SandConfig::default()
+            };
+
+            // Fill and simulate should leave the same pile
+            let mut filled = Map::with_config(&cave, &config).unwrap();
+            let mut simulated = Map::with_config(&cave, &config).unwrap();
+
+            if left {
+                assert_eq!(filled.x_offset, 0);
+            }
+
+            assert_eq!(filled.fill(), Some(simulated.simulate().len()));
+            assert!(filled.content() == simulated.content());
+        }
+
+        // Only works with the classic rules and a floor
+        let input = parse_test_vec(EXAMPLE1, input_transform).unwrap();
+
+        assert_eq!(Map::new(&input, false).unwrap().fill(), None);
+        assert_eq!(
+            Map::with_config(
+                &input,
+                &SandConfig {
+                    rules: FallRules::sliding(),
+                    floor: Some(2),
+                    ..SandConfig::default()
+                }
+            )
+            .unwrap()
+            .fill(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_resume() {
+        let input = parse_test_vec(EXAMPLE1, input_transform).unwrap();
+
+        let mut map = Map::new(&input, true).unwrap();
+        let mut count = 0;
+
+        // Each grain's full path should be returned even though it resumes part way down
+        while let DropResult::Rest(path) = map.drop_sand() {
+            assert_eq!(path[0], (500, 0));
+
+            for step in path.windows(2) {
+                let (x1, y1) = step[0];
+                let (x2, y2) = step[1];
+
+                assert_eq!(y2, y1 + 1);
+                assert!(x2.abs_diff(x1) <= 1);
+            }
+
+            let (x, y) = *path.last().unwrap();
+            assert!(!map.tile_is_empty(x, y));
+
+            count += 1;
+        }
+
+        assert_eq!(count, 93);
+    }
+
+    #[test]
+    fn test_resume_identical() {
+        let mut rnd = rng(7);
+
+        for i in 0..400 {
+            // Random cave with one to three sources
+            let cave = (0..rnd(20) + 1)
+                .map(|_| {
+                    let (x, y) = (485 + rnd(30), 2 + rnd(20));
+                    let len = rnd(8);
+
+                    if rnd(2) == 0 {
+                        vec![(x, y), (x + len, y)]
+                    } else {
+                        vec![(x, y), (x, y + len)]
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            let config = SandConfig {
+                sources: (0..rnd(3) + 1).map(|_| (490 + rnd(20), rnd(3))).collect(),
+                rules: if i % 2 == 0 {
+                    FallRules::classic()
+                } else {
+                    FallRules::sliding()
+                },
+                floor: [None, Some(1 + rnd(4))][rnd(2)],
+            };
+
+            let mut map = Map::with_config(&cave, &config).unwrap();
+            let sources = map.sources().len();
+
+            // Drop from each source in turn, comparing the resumed path with one from the top
+            for drop in 0.. {
+                let source = drop % sources;
+                let expected = fall_from_top(&map, &config, source);
+
+                match (map.drop_sand_from(source), expected) {
+                    (DropResult::Rest(path), Some((expected, true))) => assert_eq!(path, expected),
+                    (DropResult::Out(path), Some((expected, false))) => {
+                        assert_eq!(path, expected);
+                        break;
+                    }
+                    (DropResult::Full, None) => {
+                        if (0..sources).all(|s| fall_from_top(&map, &config, s).is_none()) {
+                            break;
+                        }
+                    }
+                    _ => panic!("Drop {drop} from source {source} differs"),
+                }
+            }
+        }
+    }
+
+    /// Follows a grain from its source without changing the map, returning the
+    /// path and whether it comes to rest, or None if the source is blocked
+    fn fall_from_top(
+        map: &Map,
+        config: &SandConfig,
+        source: usize,
+    ) -> Option<(Vec<(usize, usize)>, bool)> {
+        let (mut x, mut y) = config.sources[source];
+
+        if !map.tile_is_empty(x, y) {
+            return None;
+        }
+
+        let mut path = vec![];
+
+        'fall: loop {
+            path.push((x, y));
+
+            for (dx, dy) in &config.rules.moves {
+                let (nx, ny) = (x as isize + dx, y + dy);
+
+                if nx < map.x_offset as isize
+                    || nx >= (map.x_offset + map.width) as isize
+                    || ny >= map.height
+                {
+                    return Some((path, false));
+                }
+
+                let nx = nx as usize;
+
+                if map.tile_is_empty(nx, ny) && !path.iter().any(|(px, py)| (*px, *py) == (nx, ny))
+                {
+                    (x, y) = (nx, ny);
+                    continue 'fall;
+                }
+            }
+
+            return Some((path, true));
+        }
+    }
+
+    /// Returns a repeatable pseudo random number generator giving numbers below a limit
+    fn rng(mut seed: usize) -> impl FnMut(usize) -> usize {
+        move |n| {
+            seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+            (seed >> 16) % n
+        }
     }
 }
@@ -0,0 +1,29 @@
+/// Moves a falling grain of sand tries in order, as (x, y) offsets
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FallRules {
+    pub moves: Vec<(isize, usize)>,
+}
+
+impl FallRules {
+    /// Down, down and left, then down and right
+    pub fn classic() -> Self {
+        Self {
+            moves: vec![(0, 1), (-1, 1), (1, 1)],
+        }
+    }
+
+    /// Classic rules which also let a grain slide left or right when it can't fall
+    pub fn sliding() -> Self {
+        let mut rules = Self::classic();
+
+        rules.moves.extend([(-1, 0), (1, 0)]);
+
+        rules
+    }
+}
+
+impl Default for FallRules {
+    fn default() -> Self {
+        Self::classic()
+    }
+}
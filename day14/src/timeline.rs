@@ -0,0 +1,54 @@
+/// A grain of sand which came to rest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grain {
+    /// Index of the source the grain was dropped from
+    pub source: usize,
+    /// Where the grain came to rest
+    pub pos: (usize, usize),
+    /// Number of moves the grain made before coming to rest
+    pub moves: usize,
+}
+
+/// Record of a simulation run in the order grains came to rest
+#[derive(Debug, Clone, Default)]
+pub struct Timeline {
+    pub grains: Vec<Grain>,
+    /// Source of the grain which fell out of the map, or None if all sources were blocked
+    pub overflow: Option<usize>,
+}
+
+impl Timeline {
+    /// Returns the number of grains which came to rest
+    pub fn len(&self) -> usize {
+        self.grains.len()
+    }
+
+    /// Returns true if no grains came to rest
+    pub fn is_empty(&self) -> bool {
+        self.grains.is_empty()
+    }
+
+    /// Returns the rest position of each grain
+    pub fn rest_positions(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.grains.iter().map(|g| g.pos)
+    }
+
+    /// Returns the number of grains at rest when the top of the pile first reached
+    /// each new height, as (grains, y)
+    pub fn growth(&self) -> Vec<(usize, usize)> {
+        let mut top = usize::MAX;
+
+        self.grains
+            .iter()
+            .enumerate()
+            .filter_map(|(i, g)| {
+                if g.pos.1 < top {
+                    top = g.pos.1;
+                    Some((i + 1, top))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
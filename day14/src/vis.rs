@@ -14,8 +14,8 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     // Create visualisations
     println!("Generating visualisations...");
-    do_part(&input, "day14-1", false);
-    do_part(&input, "day14-2", true);
+    do_part(&input, "day14-1", false)?;
+    do_part(&input, "day14-2", true)?;
 
     Ok(())
 }
@@ -49,11 +49,11 @@ lazy_static! {
     };
 }
 
-fn do_part(input: &[InputEnt], file_stub: &str, floor: bool) {
+fn do_part(input: &[InputEnt], file_stub: &str, floor: bool) -> Result<(), Box<dyn Error>> {
     let anim_file = format!("vis/{file_stub}-anim.gif");
 
     // Create the map
-    let mut map = Map::new(input, floor);
+    let mut map = Map::new(input, floor)?;
 
     // Create background base frame
     let mut mt = MT19937::new(42);
@@ -144,6 +144,8 @@ fn do_part(input: &[InputEnt], file_stub: &str, floor: bool) {
         &last_path,
         format!("vis/{file_stub}-final.gif"),
     );
+
+    Ok(())
 }
 
 fn draw(map: &Map, frame_data: Vec<Vec<u8>>, path: &Vec<(usize, usize)>, file: String) {
@@ -176,7 +178,7 @@ fn draw_frame(
     identical_action: IdenticalAction,
 ) {
     // Draw tiles
-    for (y, row) in map.content.iter().enumerate() {
+    for (y, row) in map.content().iter().enumerate() {
         for (x, tile) in row.iter().enumerate() {
             match tile {
                 Tile::Sand => frame_data[y][x] = SAND_COLOUR,